@@ -0,0 +1,449 @@
+//! Support for parsing the JSON output of composer audit
+
+use crate::plugins::plugin_args;
+#[cfg(feature = "tokio")]
+use crate::run_composer_async;
+use crate::{
+    composer_command, log_failed_call, parse_json_output, run_composer, ComposerCommandOptions,
+    Error,
+};
+
+use std::collections::BTreeMap;
+use std::process::{Command, Output};
+
+/// These are options to modify the behaviour of composer audit
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerAuditOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// Outer structure for parsing composer-audit output
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ComposerAuditData {
+    /// All advisories affecting the locked packages, composer reports these
+    /// keyed by package name but we flatten them into a single list
+    #[serde(deserialize_with = "deserialize_advisories")]
+    pub advisories: Vec<SecurityAdvisory>,
+}
+
+/// A single security advisory for a package as reported by composer audit
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityAdvisory {
+    /// The identifier of the advisory, e.g. PKSA-xxxx-xxxx-xxxx
+    pub advisory_id: String,
+    /// Name of the affected package
+    pub package_name: String,
+    /// The constraint describing the affected versions
+    pub affected_versions: String,
+    /// Title of the advisory
+    pub title: String,
+    /// The CVE identifier, if one has been assigned
+    pub cve: Option<String>,
+    /// Link to more information about the advisory
+    pub link: Option<String>,
+    /// How severe the issue is, older composer versions do not report it
+    #[serde(default, deserialize_with = "deserialize_severity")]
+    pub severity: Severity,
+}
+
+/// The severity of a security advisory
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    /// Low severity
+    Low,
+    /// Medium severity
+    Medium,
+    /// High severity
+    High,
+    /// Critical severity
+    Critical,
+    /// The severity is unknown or was not reported
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Low => {
+                write!(f, "low")
+            }
+            Severity::Medium => {
+                write!(f, "medium")
+            }
+            Severity::High => {
+                write!(f, "high")
+            }
+            Severity::Critical => {
+                write!(f, "critical")
+            }
+            Severity::Unknown => {
+                write!(f, "unknown")
+            }
+        }
+    }
+}
+
+/// What the exit code indicated about security advisories
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndicatedAdvisoryStatus {
+    /// No advisories affect the locked packages
+    NoAdvisories,
+    /// At least one advisory affects the locked packages
+    AdvisoriesFound,
+}
+
+impl std::fmt::Display for IndicatedAdvisoryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndicatedAdvisoryStatus::NoAdvisories => {
+                write!(f, "no-advisories")
+            }
+            IndicatedAdvisoryStatus::AdvisoriesFound => {
+                write!(f, "advisories-found")
+            }
+        }
+    }
+}
+
+/// The shapes composer uses for the advisories, PHP encodes an empty map as
+/// an empty list and lists with gaps in their indices as maps
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawAdvisories {
+    /// a flat list, this is also what we serialize to
+    List(Vec<SecurityAdvisory>),
+    /// advisories keyed by package name
+    ByPackage(BTreeMap<String, RawPackageAdvisories>),
+}
+
+/// The advisories for a single package
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawPackageAdvisories {
+    /// a regular list
+    List(Vec<SecurityAdvisory>),
+    /// a list with gaps in the indices which PHP encodes as a map
+    Map(BTreeMap<String, SecurityAdvisory>),
+}
+
+/// flattens the advisories composer reports keyed by package name
+fn deserialize_advisories<'de, D>(deserializer: D) -> Result<Vec<SecurityAdvisory>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: RawAdvisories = serde::Deserialize::deserialize(deserializer)?;
+    Ok(match raw {
+        RawAdvisories::List(advisories) => advisories,
+        RawAdvisories::ByPackage(by_package) => by_package
+            .into_values()
+            .flat_map(|advisories| match advisories {
+                RawPackageAdvisories::List(advisories) => advisories,
+                RawPackageAdvisories::Map(advisories) => advisories.into_values().collect(),
+            })
+            .collect(),
+    })
+}
+
+/// composer reports null for advisories without a known severity
+fn deserialize_severity<'de, D>(deserializer: D) -> Result<Severity, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let severity: Option<Severity> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(severity.unwrap_or_default())
+}
+
+impl Severity {
+    /// the SARIF level used for advisories of this severity
+    fn sarif_level(&self) -> &'static str {
+        match self {
+            Severity::Critical | Severity::High => "error",
+            Severity::Medium | Severity::Unknown => "warning",
+            Severity::Low => "note",
+        }
+    }
+
+    /// the score GitHub code scanning uses to rank security results, None
+    /// if the severity is unknown
+    fn security_severity(&self) -> Option<&'static str> {
+        match self {
+            Severity::Critical => Some("9.5"),
+            Severity::High => Some("8.0"),
+            Severity::Medium => Some("5.5"),
+            Severity::Low => Some("2.0"),
+            Severity::Unknown => None,
+        }
+    }
+}
+
+impl ComposerAuditData {
+    /// renders the advisories as a SARIF 2.1.0 document with one rule per
+    /// advisory and one result per affected package, e.g. for GitHub code
+    /// scanning, all results point at composer.lock
+    pub fn to_sarif(&self) -> String {
+        let mut rules = BTreeMap::new();
+        for advisory in &self.advisories {
+            rules
+                .entry(advisory.advisory_id.as_str())
+                .or_insert_with(|| {
+                    let mut tags = vec!["security"];
+                    tags.extend(advisory.cve.as_deref());
+                    let mut properties = serde_json::Map::new();
+                    properties.insert("tags".to_string(), serde_json::json!(tags));
+                    if let Some(score) = advisory.severity.security_severity() {
+                        properties.insert("security-severity".to_string(), score.into());
+                    }
+                    let mut rule = serde_json::json!({
+                        "id": advisory.advisory_id,
+                        "shortDescription": { "text": advisory.title },
+                        "defaultConfiguration": { "level": advisory.severity.sarif_level() },
+                        "properties": properties,
+                    });
+                    if let Some(link) = &advisory.link {
+                        rule["helpUri"] = link.as_str().into();
+                    }
+                    rule
+                });
+        }
+        let results: Vec<serde_json::Value> = self
+            .advisories
+            .iter()
+            .map(|advisory| {
+                serde_json::json!({
+                    "ruleId": advisory.advisory_id,
+                    "level": advisory.severity.sarif_level(),
+                    "message": {
+                        "text": format!(
+                            "{} ({}): {}",
+                            advisory.package_name, advisory.affected_versions, advisory.title
+                        ),
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "composer.lock" },
+                        },
+                    }],
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "composer audit",
+                        "informationUri": "https://getcomposer.org/doc/03-cli.md#audit",
+                        "rules": rules.into_values().collect::<Vec<_>>(),
+                    },
+                },
+                "results": results,
+            }],
+        })
+        .to_string()
+    }
+}
+
+/// creates the composer audit command from the options
+fn audit_command(options: &ComposerAuditOptions) -> Result<Command, Error> {
+    let mut cmd = composer_command(&options.command)?;
+
+    cmd.args(["audit", "--format=json"])
+        .args(plugin_args(&options.command))
+        .arg("--locked");
+
+    Ok(cmd)
+}
+
+/// main entry point for the composer-audit call
+pub fn audit(
+    options: &ComposerAuditOptions,
+) -> Result<(IndicatedAdvisoryStatus, ComposerAuditData), Error> {
+    let output = run_composer(audit_command(options)?, &options.command)?;
+    audit_output(&output)
+}
+
+/// async variant of [audit] which runs composer using tokio
+#[cfg(feature = "tokio")]
+pub async fn audit_async(
+    options: &ComposerAuditOptions,
+) -> Result<(IndicatedAdvisoryStatus, ComposerAuditData), Error> {
+    let output = run_composer_async(audit_command(options)?, &options.command).await?;
+    audit_output(&output)
+}
+
+/// interprets the output of the composer audit call, shared by the sync and
+/// async variants
+fn audit_output(output: &Output) -> Result<(IndicatedAdvisoryStatus, ComposerAuditData), Error> {
+    if !output.status.success() {
+        log_failed_call("audit", output);
+    }
+
+    let advisory_status = if output.status.success() {
+        IndicatedAdvisoryStatus::NoAdvisories
+    } else {
+        IndicatedAdvisoryStatus::AdvisoriesFound
+    };
+
+    let data: ComposerAuditData = parse_json_output(output)?;
+    Ok((advisory_status, data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_audit_output() -> Result<(), Error> {
+        let json = r#"{
+            "advisories": {
+                "guzzlehttp/psr7": [
+                    {
+                        "advisoryId": "PKSA-p5w4-tj2s-8k2h",
+                        "packageName": "guzzlehttp/psr7",
+                        "affectedVersions": ">=2,<2.4.5",
+                        "title": "Improper header validation",
+                        "cve": "CVE-2023-29197",
+                        "link": "https://github.com/guzzle/psr7/security/advisories/GHSA-wxmh-65f7-jcvw",
+                        "reportedAt": "2023-04-17T16:00:00+00:00",
+                        "sources": [
+                            {
+                                "name": "GitHub",
+                                "remoteId": "GHSA-wxmh-65f7-jcvw"
+                            }
+                        ],
+                        "severity": "medium"
+                    }
+                ],
+                "symfony/http-kernel": {
+                    "1": {
+                        "advisoryId": "PKSA-xxxx-yyyy-zzzz",
+                        "packageName": "symfony/http-kernel",
+                        "affectedVersions": ">=6.0,<6.0.20",
+                        "title": "Some other issue",
+                        "cve": null,
+                        "link": "https://symfony.com/blog",
+                        "reportedAt": "2023-01-01T00:00:00+00:00",
+                        "sources": [],
+                        "severity": null
+                    }
+                }
+            }
+        }"#;
+        let data: ComposerAuditData = serde_json::from_str(json)?;
+        assert_eq!(data.advisories.len(), 2);
+        assert_eq!(data.advisories[0].package_name, "guzzlehttp/psr7");
+        assert_eq!(data.advisories[0].cve.as_deref(), Some("CVE-2023-29197"));
+        assert_eq!(data.advisories[0].severity, Severity::Medium);
+        assert_eq!(data.advisories[1].advisory_id, "PKSA-xxxx-yyyy-zzzz");
+        assert_eq!(data.advisories[1].cve, None);
+        assert_eq!(data.advisories[1].severity, Severity::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_sarif() -> Result<(), Error> {
+        let data: ComposerAuditData = serde_json::from_str(
+            r#"{
+                "advisories": {
+                    "guzzlehttp/psr7": [
+                        {
+                            "advisoryId": "PKSA-p5w4-tj2s-8k2h",
+                            "packageName": "guzzlehttp/psr7",
+                            "affectedVersions": ">=2,<2.4.5",
+                            "title": "Improper header validation",
+                            "cve": "CVE-2023-29197",
+                            "link": "https://github.com/guzzle/psr7/security/advisories/GHSA-wxmh-65f7-jcvw",
+                            "severity": "high"
+                        }
+                    ],
+                    "symfony/http-kernel": [
+                        {
+                            "advisoryId": "PKSA-xxxx-yyyy-zzzz",
+                            "packageName": "symfony/http-kernel",
+                            "affectedVersions": ">=6.0,<6.0.20",
+                            "title": "Some other issue",
+                            "cve": null,
+                            "link": null,
+                            "severity": "low"
+                        }
+                    ]
+                }
+            }"#,
+        )?;
+        let sarif: serde_json::Value = serde_json::from_str(&data.to_sarif())?;
+        // the properties SARIF 2.1.0 requires
+        assert_eq!(
+            sarif["$schema"],
+            "https://json.schemastore.org/sarif-2.1.0.json"
+        );
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["runs"].as_array().map(Vec::len), Some(1));
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "composer audit");
+        let rules = run["tool"]["driver"]["rules"]
+            .as_array()
+            .expect("rules should be an array");
+        let results = run["results"]
+            .as_array()
+            .expect("results should be an array");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(results.len(), 2);
+        for rule in rules {
+            assert!(rule["id"].is_string());
+        }
+        for result in results {
+            // every result has to reference a rule and carry a message
+            assert!(rules.iter().any(|rule| rule["id"] == result["ruleId"]));
+            assert!(result["ruleId"].is_string());
+            assert!(["none", "note", "warning", "error"]
+                .iter()
+                .any(|level| result["level"] == *level));
+            assert!(result["message"]["text"].is_string());
+            assert_eq!(
+                result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+                "composer.lock"
+            );
+        }
+        assert_eq!(results[0]["ruleId"], "PKSA-p5w4-tj2s-8k2h");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["message"]["text"],
+            "guzzlehttp/psr7 (>=2,<2.4.5): Improper header validation"
+        );
+        assert_eq!(results[1]["level"], "note");
+        assert_eq!(rules[0]["properties"]["tags"][1], "CVE-2023-29197");
+        assert_eq!(rules[0]["properties"]["security-severity"], "8.0");
+        assert_eq!(
+            rules[0]["helpUri"],
+            "https://github.com/guzzle/psr7/security/advisories/GHSA-wxmh-65f7-jcvw"
+        );
+        assert!(rules[1].get("helpUri").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_sarif_without_advisories() -> Result<(), Error> {
+        let data: ComposerAuditData = serde_json::from_str(r#"{"advisories": []}"#)?;
+        let sarif: serde_json::Value = serde_json::from_str(&data.to_sarif())?;
+        assert_eq!(sarif["runs"][0]["results"], serde_json::json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_audit_output_without_advisories() -> Result<(), Error> {
+        let data: ComposerAuditData = serde_json::from_str(r#"{"advisories": []}"#)?;
+        assert!(data.advisories.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Support for previewing the constraint changes composer bump would make
+
+use crate::plugins::plugin_args;
+use crate::project::project_directory;
+use crate::{
+    command_failed, log_failed_call, parse_manifest, run_composer, text_composer_command,
+    ComposerCommandOptions, Error, Manifest,
+};
+
+use std::str::from_utf8;
+use tracing::debug;
+
+/// These are options to modify the behaviour of composer bump
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerBumpOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// A constraint in composer.json composer bump would change
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConstraintBump {
+    /// Name of the required package
+    pub package: String,
+    /// The constraint currently in composer.json, composer does not report
+    /// it so it is read from composer.json, None if that is not possible
+    pub from: Option<String>,
+    /// The constraint composer would write, e.g. `^1.2.3`
+    pub to: String,
+    /// Is the package required in require-dev
+    pub dev: bool,
+}
+
+/// parses the text output of composer bump --dry-run, every change is
+/// reported as e.g. ` - require.vendor/package: ^1.2.3` below a line saying
+/// composer.json would be updated, the current constraints are looked up in
+/// the manifest if there is one
+fn parse_bump_output(output: &str, manifest: Option<&Manifest>) -> Vec<ConstraintBump> {
+    let mut bumps = vec![];
+    for line in output.lines() {
+        let Some(change) = line.trim().strip_prefix("- ") else {
+            continue;
+        };
+        let Some((key, to)) = change.split_once(": ") else {
+            debug!(line, "unrecognized composer bump line");
+            continue;
+        };
+        let (dev, package) = if let Some(package) = key.strip_prefix("require-dev.") {
+            (true, package)
+        } else if let Some(package) = key.strip_prefix("require.") {
+            (false, package)
+        } else {
+            debug!(line, "unrecognized composer bump line");
+            continue;
+        };
+        let from = manifest.and_then(|manifest| {
+            let require = if dev {
+                &manifest.require_dev
+            } else {
+                &manifest.require
+            };
+            require.get(package).cloned()
+        });
+        bumps.push(ConstraintBump {
+            package: package.to_string(),
+            from,
+            to: to.trim().to_string(),
+            dev,
+        });
+    }
+    bumps
+}
+
+/// main entry point for the composer-bump call, returns the constraints
+/// composer bump would change in composer.json without changing it, this
+/// requires composer 2.4 or later and is empty if there is nothing to bump
+pub fn bump_preview(options: &ComposerBumpOptions) -> Result<Vec<ConstraintBump>, Error> {
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.args(["bump", "--dry-run", "--no-ansi"])
+        .args(plugin_args(&options.command));
+
+    let output = run_composer(cmd, &options.command)?;
+
+    // the global composer.json is in the composer home directory which we
+    // do not know so the current constraints are only looked up for projects
+    let manifest = if options.command.global {
+        None
+    } else {
+        let path = project_directory(&options.command).join("composer.json");
+        match parse_manifest(&path) {
+            Ok(manifest) => Some(manifest),
+            Err(err) => {
+                debug!(path = %path.display(), error = %err, "could not read composer.json");
+                None
+            }
+        }
+    };
+
+    let bumps = parse_bump_output(from_utf8(&output.stdout)?, manifest.as_ref());
+
+    // composer exits with an unsuccessful exit code in dry-run mode if it
+    // would change something so this is only a failure if there is no output
+    if !output.status.success() && bumps.is_empty() {
+        log_failed_call("bump", &output);
+        return Err(command_failed(&output));
+    }
+
+    Ok(bumps)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_bump_output() -> Result<(), Error> {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{
+                "require": { "monolog/monolog": "^3.0" },
+                "require-dev": { "phpunit/phpunit": "^10.0" }
+            }"#,
+        )?;
+        let output = "\
+./composer.json would be updated with:
+ - require.monolog/monolog: ^3.5.0
+ - require-dev.phpunit/phpunit: ^10.5.10
+ - require.symfony/console: ^7.1.2
+";
+        assert_eq!(
+            parse_bump_output(output, Some(&manifest)),
+            vec![
+                ConstraintBump {
+                    package: "monolog/monolog".to_string(),
+                    from: Some("^3.0".to_string()),
+                    to: "^3.5.0".to_string(),
+                    dev: false,
+                },
+                ConstraintBump {
+                    package: "phpunit/phpunit".to_string(),
+                    from: Some("^10.0".to_string()),
+                    to: "^10.5.10".to_string(),
+                    dev: true,
+                },
+                ConstraintBump {
+                    package: "symfony/console".to_string(),
+                    from: None,
+                    to: "^7.1.2".to_string(),
+                    dev: false,
+                },
+            ]
+        );
+        assert_eq!(
+            parse_bump_output(" - require.monolog/monolog: ^3.5.0", None)[0].from,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bump_output_nothing_to_bump() {
+        assert_eq!(
+            parse_bump_output("No requirements to update in ./composer.json.\n", None),
+            vec![]
+        );
+    }
+}
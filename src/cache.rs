@@ -0,0 +1,224 @@
+//! Caching of composer outdated results keyed by the content of composer.lock
+
+use crate::{lock_file_path, plan, ComposerOutdatedOptions, Error};
+
+use std::hash::{Hash as _, Hasher as _};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+/// Where and for how long the results of composer outdated are cached
+///
+/// The cache is keyed by the content of composer.lock and the composer call
+/// as [plan] assembles it, the program, its arguments, working directory and
+/// the environment variables set for it, so changing any of them causes
+/// composer to be called again, it stores the output of composer before parsing so options which
+/// only affect the parsing and filtering like
+/// [only_packages](ComposerOutdatedOptions::only_packages) apply to cached
+/// results the same way as to fresh ones, newer releases of packages on
+/// Packagist are not noticed until the cached result is older than the TTL,
+/// this staleness is the price for not calling composer on every run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// The directory the cached results are stored in, it is created if it
+    /// does not exist
+    pub dir: PathBuf,
+    /// How long a cached result is used before composer is called again
+    pub ttl: Duration,
+}
+
+/// the default TTL if none is given on the command line
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// parses the cache command line argument in the form DIR[:SECONDS]
+pub(crate) fn parse_cache_policy(s: &str) -> Result<CachePolicy, String> {
+    let (dir, ttl) = match s.rsplit_once(':').map(|(dir, ttl)| (dir, ttl.parse())) {
+        Some((dir, Ok(ttl))) => (dir, Duration::from_secs(ttl)),
+        _ => (s, DEFAULT_TTL),
+    };
+    if dir.is_empty() {
+        return Err("cache directory is empty".to_string());
+    }
+    Ok(CachePolicy {
+        dir: PathBuf::from(dir),
+        ttl,
+    })
+}
+
+/// A single cached composer outdated result
+#[derive(Debug)]
+pub(crate) struct CacheEntry {
+    /// the file the result is stored in
+    path: PathBuf,
+    /// how long the stored result is used
+    ttl: Duration,
+}
+
+/// The content of a cache file, the output of composer outdated as it was
+/// returned by composer
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CachedOutput {
+    /// the exit code of composer
+    exit_code: i32,
+    /// everything composer wrote to stdout
+    stdout: Vec<u8>,
+    /// everything composer wrote to stderr
+    stderr: Vec<u8>,
+}
+
+/// the exit status for the given exit code
+fn exit_status(exit_code: i32) -> ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+        ExitStatus::from_raw(exit_code << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt as _;
+        ExitStatus::from_raw(exit_code as u32)
+    }
+}
+
+impl From<&Output> for CachedOutput {
+    fn from(output: &Output) -> Self {
+        CachedOutput {
+            // composer is not killed by a signal without the call failing
+            exit_code: output.status.code().unwrap_or(1),
+            stdout: output.stdout.clone(),
+            stderr: output.stderr.clone(),
+        }
+    }
+}
+
+impl From<CachedOutput> for Output {
+    fn from(cached: CachedOutput) -> Self {
+        Output {
+            status: exit_status(cached.exit_code),
+            stdout: cached.stdout,
+            stderr: cached.stderr,
+        }
+    }
+}
+
+/// wraps an I/O error with the path of the cache file it occurred on
+fn cache_io(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |source| Error::CacheIo {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+impl CacheEntry {
+    /// the cache entry for the given options, None if caching is disabled,
+    /// there is no composer.lock to key the cache by or the composer call
+    /// can not be assembled
+    pub(crate) fn for_options(options: &ComposerOutdatedOptions) -> Option<CacheEntry> {
+        let policy = options.cache.as_ref()?;
+        if options.command.global {
+            debug!("not caching the results for global packages");
+            return None;
+        }
+        let lock_file_path = lock_file_path(&options.command);
+        let lock_file = match std::fs::read(&lock_file_path) {
+            Ok(lock_file) => lock_file,
+            Err(err) => {
+                debug!(
+                    path = %lock_file_path.display(),
+                    error = %err,
+                    "could not read composer.lock to key the cache"
+                );
+                return None;
+            }
+        };
+        let invocation = match plan(options) {
+            Ok(invocation) => invocation,
+            Err(err) => {
+                debug!(error = %err, "could not assemble the composer call to key the cache");
+                return None;
+            }
+        };
+        // the hasher of the standard library is not guaranteed to be stable
+        // between Rust versions, this only causes a cache miss after updates
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        lock_file.hash(&mut hasher);
+        invocation.hash(&mut hasher);
+        Some(CacheEntry {
+            path: policy
+                .dir
+                .join(format!("outdated-{:016x}.json", hasher.finish())),
+            ttl: policy.ttl,
+        })
+    }
+
+    /// the cached output of composer if there is one which is younger than
+    /// the TTL
+    pub(crate) fn load(&self) -> Result<Option<Output>, Error> {
+        let modified = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata.modified().map_err(cache_io(&self.path))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(cache_io(&self.path)(err)),
+        };
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or(Duration::ZERO);
+        if age >= self.ttl {
+            debug!(path = %self.path.display(), age_secs = age.as_secs(), "cached result expired");
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&self.path).map_err(cache_io(&self.path))?;
+        match serde_json::from_str::<CachedOutput>(&content) {
+            Ok(cached) => {
+                debug!(path = %self.path.display(), "using cached result");
+                Ok(Some(cached.into()))
+            }
+            Err(err) => {
+                debug!(path = %self.path.display(), error = %err, "ignoring unreadable cached result");
+                Ok(None)
+            }
+        }
+    }
+
+    /// stores the output of composer in the cache
+    pub(crate) fn store(&self, output: &Output) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(cache_io(dir))?;
+        }
+        let content = serde_json::to_string(&CachedOutput::from(output))?;
+        std::fs::write(&self.path, content).map_err(cache_io(&self.path))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_cache_policy() {
+        assert_eq!(
+            parse_cache_policy("/tmp/cache:60"),
+            Ok(CachePolicy {
+                dir: PathBuf::from("/tmp/cache"),
+                ttl: Duration::from_secs(60),
+            })
+        );
+        assert_eq!(
+            parse_cache_policy("/tmp/cache"),
+            Ok(CachePolicy {
+                dir: PathBuf::from("/tmp/cache"),
+                ttl: DEFAULT_TTL,
+            })
+        );
+        assert_eq!(
+            parse_cache_policy("C:/cache"),
+            Ok(CachePolicy {
+                dir: PathBuf::from("C:/cache"),
+                ttl: DEFAULT_TTL,
+            })
+        );
+        assert!(parse_cache_policy("").is_err());
+    }
+}
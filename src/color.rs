@@ -0,0 +1,100 @@
+//! Terminal colors for the update status of packages
+
+use crate::{PackageStatus, UpdateRequirement};
+
+/// The color used to display the status of a package in a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusColor {
+    /// The package is up to date
+    Green,
+    /// A semver-compatible update is available
+    Yellow,
+    /// An update which is not semver-compatible is available
+    Red,
+    /// The package has been abandoned
+    Magenta,
+}
+
+impl StatusColor {
+    /// the ANSI escape sequence selecting this color as foreground color
+    pub fn ansi_code(&self) -> &'static str {
+        match self {
+            StatusColor::Green => "\x1b[32m",
+            StatusColor::Yellow => "\x1b[33m",
+            StatusColor::Red => "\x1b[31m",
+            StatusColor::Magenta => "\x1b[35m",
+        }
+    }
+
+    /// wraps the text in the ANSI escape sequences for this color
+    pub fn paint(&self, text: &str) -> String {
+        format!("{}{}\x1b[0m", self.ansi_code(), text)
+    }
+}
+
+impl UpdateRequirement {
+    /// the color used to display this update requirement
+    pub fn color(&self) -> StatusColor {
+        match self {
+            UpdateRequirement::UpToDate => StatusColor::Green,
+            UpdateRequirement::SemverSafeUpdate => StatusColor::Yellow,
+            UpdateRequirement::UpdatePossible => StatusColor::Red,
+        }
+    }
+}
+
+impl PackageStatus {
+    /// the color used to display the status of this package, abandoned
+    /// packages are highlighted regardless of their update requirement
+    pub fn status_color(&self) -> StatusColor {
+        if self.is_abandoned() {
+            StatusColor::Magenta
+        } else {
+            self.latest_status.color()
+        }
+    }
+
+    /// the update requirement of this package colored for a terminal
+    pub fn colored_status(&self) -> String {
+        self.status_color().paint(&self.latest_status.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::package_name::test::test_package;
+    use pretty_assertions::assert_eq;
+
+    /// creates a package status with the given update requirement and warning
+    fn package(latest_status: UpdateRequirement, warning: Option<&str>) -> PackageStatus {
+        PackageStatus {
+            warning: warning.map(str::to_string),
+            ..test_package("vendor/package", "1.0.0", "1.0.1", latest_status)
+        }
+    }
+
+    #[test]
+    fn test_colored_status() {
+        assert_eq!(
+            package(UpdateRequirement::UpToDate, None).colored_status(),
+            "\x1b[32mup-to-date\x1b[0m"
+        );
+        assert_eq!(
+            package(UpdateRequirement::SemverSafeUpdate, None).colored_status(),
+            "\x1b[33msemver-safe-update\x1b[0m"
+        );
+        assert_eq!(
+            package(UpdateRequirement::UpdatePossible, None).colored_status(),
+            "\x1b[31mupdate-possible\x1b[0m"
+        );
+        assert_eq!(
+            package(
+                UpdateRequirement::UpToDate,
+                Some("Package vendor/package is abandoned, you should avoid using it. No replacement was suggested.")
+            )
+            .status_color(),
+            StatusColor::Magenta
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Deserialization of the options from configuration files
+//!
+//! The option structs can be deserialized with serde, e.g. from a JSON or
+//! TOML file, using the long names of the command line flags of the fields
+//! as keys, durations are given as a number of seconds and values with a
+//! more complex command line syntax (plugins, retry and cache) use the same
+//! string form as on the command line
+
+use crate::cache::parse_cache_policy;
+use crate::plugins::parse_plugin_mode;
+use crate::{parse_memory_limit, parse_retry_policy, CachePolicy, PluginMode, RetryPolicy};
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// deserializes a string and parses it with the given command line parser
+fn parse_with<'de, D, T>(
+    deserializer: D,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: String = serde::Deserialize::deserialize(deserializer)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+/// deserializes an optional duration given as a number of seconds
+pub(crate) fn deserialize_optional_secs<'de, D>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let secs: Option<f64> = serde::Deserialize::deserialize(deserializer)?;
+    secs.map(|secs| Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// deserializes environment variables given as a map from name to value
+pub(crate) fn deserialize_env<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let env: BTreeMap<String, String> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(env.into_iter().collect())
+}
+
+/// deserializes a PHP memory limit like 2G or -1
+pub(crate) fn deserialize_optional_memory_limit<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    parse_with(deserializer, parse_memory_limit).map(Some)
+}
+
+/// deserializes the plugin mode in its command line form
+pub(crate) fn deserialize_plugin_mode<'de, D>(deserializer: D) -> Result<PluginMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    parse_with(deserializer, parse_plugin_mode)
+}
+
+/// deserializes a retry policy in its command line form ATTEMPTS[:SECONDS]
+pub(crate) fn deserialize_optional_retry_policy<'de, D>(
+    deserializer: D,
+) -> Result<Option<RetryPolicy>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    parse_with(deserializer, parse_retry_policy).map(Some)
+}
+
+/// deserializes a cache policy in its command line form DIR[:SECONDS]
+pub(crate) fn deserialize_optional_cache_policy<'de, D>(
+    deserializer: D,
+) -> Result<Option<CachePolicy>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    parse_with(deserializer, parse_cache_policy).map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        build_args, ComposerAuditOptions, ComposerOutdatedOptions, OutdatedScope, PackageName,
+        PluginMode,
+    };
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn test_deserialize_outdated_options() -> Result<(), serde_json::Error> {
+        let options: ComposerOutdatedOptions = serde_json::from_str(
+            r#"{
+                "ignore": ["symfony/console"],
+                "direct-only": true,
+                "scope": "all",
+                "only-packages": ["doctrine/orm"],
+                "working-directory": "/srv/project",
+                "timeout": 1.5,
+                "env": { "COMPOSER_HOME": "/tmp/composer" },
+                "plugins": "all",
+                "retry": "3:2",
+                "cache": "/tmp/cache:60"
+            }"#,
+        )?;
+        assert_eq!(options.ignored_packages, vec!["symfony/console"]);
+        assert_eq!(
+            options.only_packages,
+            vec![PackageName::new("doctrine/orm").expect("package name should be valid")]
+        );
+        assert_eq!(options.scope, OutdatedScope::All);
+        assert_eq!(
+            options.command.working_directory,
+            Some(PathBuf::from("/srv/project"))
+        );
+        assert_eq!(options.command.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(
+            options.command.env,
+            vec![("COMPOSER_HOME".to_string(), "/tmp/composer".to_string())]
+        );
+        assert_eq!(options.command.plugins, PluginMode::All);
+        assert_eq!(
+            options.command.retry.as_ref().map(|retry| retry.attempts),
+            Some(3)
+        );
+        assert_eq!(
+            options.cache.as_ref().map(|cache| cache.ttl),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            build_args(&options),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--strict",
+                "--locked",
+                "--direct",
+                "--ignore",
+                "symfony/console",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_defaults() -> Result<(), serde_json::Error> {
+        let options: ComposerOutdatedOptions = serde_json::from_str("{}")?;
+        assert_eq!(
+            build_args(&options),
+            build_args(&ComposerOutdatedOptions::default())
+        );
+        let options: ComposerAuditOptions = serde_json::from_str(r#"{ "global": true }"#)?;
+        assert!(options.command.global);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_invalid() {
+        assert!(serde_json::from_str::<ComposerOutdatedOptions>(r#"{ "retry": "many" }"#).is_err());
+        assert!(serde_json::from_str::<ComposerOutdatedOptions>(r#"{ "timeout": -1 }"#).is_err());
+        assert!(
+            serde_json::from_str::<ComposerOutdatedOptions>(r#"{ "memory-limit": "lots" }"#)
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<ComposerOutdatedOptions>(r#"{ "ignore": ["invalid"] }"#)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Matching of versions against composer version constraints
+
+use crate::{is_branch_version, parse_composer_version, PackageStatus};
+
+/// one alternative of a constraint, all of the comparators have to match
+/// and the version must not be one of the excluded ones
+#[derive(Debug)]
+struct ConstraintGroup {
+    /// the comparators translated to the semver crate
+    req: semver::VersionReq,
+    /// versions excluded with `!=`
+    excluded: Vec<semver::Version>,
+}
+
+/// the comparison operators composer supports in front of a version
+const OPERATORS: &[&str] = &[">=", "<=", "==", "!=", "<>", ">", "<", "="];
+
+/// a single composer comparator translated for the semver crate
+#[derive(Debug)]
+enum Translated {
+    /// a comparator in the syntax of the semver crate
+    Comparator(String),
+    /// a version excluded with `!=`, which the semver crate does not support
+    Excluded(semver::Version),
+    /// only a stability flag like `@dev` which does not restrict the version
+    Unrestricted,
+}
+
+/// strips the optional `v` prefix of a version in a constraint
+fn strip_v(version: &str) -> &str {
+    version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .unwrap_or(version)
+}
+
+/// translates a single composer comparator like `^1.2` or `>=1.0` into the
+/// syntax of the semver crate, the semver crate treats a bare version as a
+/// caret requirement and `~1.2` as `>=1.2.0 <1.3.0` while composer treats the
+/// former as an exact version and the latter as `>=1.2.0 <2.0.0`, None if
+/// the comparator can not be translated
+fn translate_comparator(comparator: &str) -> Option<Translated> {
+    let comparator = comparator
+        .split_once('@')
+        .map_or(comparator, |(comparator, _stability)| comparator);
+    if comparator.is_empty() {
+        return Some(Translated::Unrestricted);
+    }
+    if comparator == "*" {
+        return Some(Translated::Comparator("*".to_string()));
+    }
+    if let Some(version) = comparator.strip_prefix('^') {
+        return Some(Translated::Comparator(format!("^{}", strip_v(version))));
+    }
+    if let Some(version) = comparator.strip_prefix('~') {
+        let version = strip_v(version);
+        return Some(Translated::Comparator(match version.split_once('.') {
+            Some((major, minor)) if !minor.contains('.') => {
+                let major: u64 = major.parse().ok()?;
+                format!(">={}.{}.0, <{}.0.0", major, minor, major + 1)
+            }
+            _ => format!("~{}", version),
+        }));
+    }
+    if let Some(operator) = OPERATORS.iter().find(|op| comparator.starts_with(**op)) {
+        let version = strip_v(comparator[operator.len()..].trim());
+        return Some(match *operator {
+            "!=" | "<>" => Translated::Excluded(parse_composer_version(version).ok()?),
+            "==" | "=" => {
+                Translated::Comparator(format!("={}", parse_composer_version(version).ok()?))
+            }
+            operator => Translated::Comparator(format!("{}{}", operator, version)),
+        });
+    }
+    if comparator.contains(['*', 'x', 'X']) {
+        return Some(Translated::Comparator(strip_v(comparator).to_string()));
+    }
+    // a bare version is an exact match in composer
+    Some(Translated::Comparator(format!(
+        "={}",
+        parse_composer_version(comparator).ok()?
+    )))
+}
+
+/// splits an alternative of a constraint into its comparators, composer
+/// separates them with commas or whitespace, allows whitespace between an
+/// operator and its version and supports hyphenated ranges like `1.0 - 2.0`
+fn comparators(group: &str) -> Vec<String> {
+    let tokens: Vec<&str> = group
+        .split([',', ' ', '\t'])
+        .filter(|token| !token.is_empty())
+        .collect();
+    let mut comparators = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens.get(i + 1) == Some(&"-") && i + 2 < tokens.len() {
+            comparators.push(format!(">={}", tokens[i]));
+            comparators.push(format!("<={}", tokens[i + 2]));
+            i += 3;
+        } else if OPERATORS.contains(&tokens[i]) && i + 1 < tokens.len() {
+            comparators.push(format!("{}{}", tokens[i], tokens[i + 1]));
+            i += 2;
+        } else {
+            comparators.push(tokens[i].to_string());
+            i += 1;
+        }
+    }
+    comparators
+}
+
+/// parses a composer constraint into its alternatives, None if composer
+/// syntax is used which can not be translated, e.g. a branch like
+/// `dev-main`
+fn parse_constraint(constraint: &str) -> Option<Vec<ConstraintGroup>> {
+    constraint
+        .split("||")
+        .flat_map(|group| group.split('|'))
+        .map(|group| {
+            let mut excluded = vec![];
+            let mut translated = vec![];
+            for comparator in comparators(group) {
+                match translate_comparator(&comparator)? {
+                    Translated::Comparator(comparator) => translated.push(comparator),
+                    Translated::Excluded(version) => excluded.push(version),
+                    Translated::Unrestricted => {}
+                }
+            }
+            let req = if translated.is_empty() {
+                semver::VersionReq::STAR
+            } else {
+                semver::VersionReq::parse(&translated.join(", ")).ok()?
+            };
+            Some(ConstraintGroup { req, excluded })
+        })
+        .collect()
+}
+
+impl PackageStatus {
+    /// does the latest version satisfy the given composer constraint, e.g.
+    /// the one for the package in composer.json, composer's caret, tilde,
+    /// wildcard, hyphenated and comparison constraints combined with `,`,
+    /// whitespace and `||` are supported, stability flags like `@dev` are
+    /// ignored, None if the constraint can not be interpreted or the latest
+    /// version is not a semantic version
+    ///
+    /// Pre-releases only satisfy a constraint which mentions a pre-release
+    /// of the same version, following the semver crate instead of composer's
+    /// minimum-stability
+    pub fn latest_satisfies(&self, constraint: &str) -> Option<bool> {
+        if is_branch_version(&self.latest) {
+            return None;
+        }
+        let latest = self.parsed_latest().ok()?;
+        let groups = parse_constraint(constraint.trim())?;
+        Some(
+            groups
+                .iter()
+                .any(|group| group.req.matches(&latest) && !group.excluded.contains(&latest)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::package_name::test::test_package;
+    use crate::UpdateRequirement;
+    use pretty_assertions::assert_eq;
+
+    /// creates a package status with the given latest version
+    fn package(latest: &str) -> PackageStatus {
+        test_package(
+            "vendor/package",
+            "1.0.0",
+            latest,
+            UpdateRequirement::UpdatePossible,
+        )
+    }
+
+    #[test]
+    fn test_latest_satisfies_caret() {
+        assert_eq!(package("1.9.0").latest_satisfies("^1.2"), Some(true));
+        assert_eq!(package("2.0.0").latest_satisfies("^1.2"), Some(false));
+        assert_eq!(package("1.1.0").latest_satisfies("^1.2"), Some(false));
+        assert_eq!(package("0.3.5").latest_satisfies("^0.3"), Some(true));
+        assert_eq!(package("0.4.0").latest_satisfies("^0.3"), Some(false));
+        assert_eq!(package("v1.2.3").latest_satisfies("^v1.2"), Some(true));
+    }
+
+    #[test]
+    fn test_latest_satisfies_tilde() {
+        assert_eq!(package("2.9.0").latest_satisfies("~2.0"), Some(true));
+        assert_eq!(package("3.0.0").latest_satisfies("~2.0"), Some(false));
+        assert_eq!(package("0.9.0").latest_satisfies("~0.2"), Some(true));
+        assert_eq!(package("1.2.9").latest_satisfies("~1.2.3"), Some(true));
+        assert_eq!(package("1.3.0").latest_satisfies("~1.2.3"), Some(false));
+    }
+
+    #[test]
+    fn test_latest_satisfies_range() {
+        assert_eq!(package("1.5.0").latest_satisfies(">=1.0 <2.0"), Some(true));
+        assert_eq!(package("2.0.0").latest_satisfies(">=1.0 <2.0"), Some(false));
+        assert_eq!(package("1.5.0").latest_satisfies(">=1.0,<2.0"), Some(true));
+        assert_eq!(
+            package("1.5.0").latest_satisfies(">= 1.0, < 2.0"),
+            Some(true)
+        );
+        assert_eq!(package("2.0.5").latest_satisfies("1.0 - 2.0"), Some(true));
+        assert_eq!(package("2.1.0").latest_satisfies("1.0 - 2.0"), Some(false));
+        assert_eq!(
+            package("1.5.0").latest_satisfies(">=1.0 !=1.5.0"),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_latest_satisfies_other_forms() {
+        assert_eq!(
+            package("3.1.0").latest_satisfies("^2.0 || ^3.0"),
+            Some(true)
+        );
+        assert_eq!(package("3.1.0").latest_satisfies("^1.0|^2.0"), Some(false));
+        assert_eq!(package("1.2.7").latest_satisfies("1.2.*"), Some(true));
+        assert_eq!(package("1.3.0").latest_satisfies("1.2.*"), Some(false));
+        assert_eq!(package("1.2.3").latest_satisfies("1.2.3"), Some(true));
+        assert_eq!(package("1.2.4").latest_satisfies("1.2.3"), Some(false));
+        assert_eq!(package("9.0.0").latest_satisfies("*"), Some(true));
+        assert_eq!(package("1.2.0").latest_satisfies("^1.0@dev"), Some(true));
+        assert_eq!(package("1.2.0").latest_satisfies("dev-main"), None);
+        assert_eq!(package("dev-main").latest_satisfies("^1.0"), None);
+    }
+}
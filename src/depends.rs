@@ -0,0 +1,356 @@
+//! Support for parsing the text and tree output of composer depends (also
+//! known as composer why)
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, log_failed_call, run_composer, text_composer_command, ComposerCommandOptions,
+    Error,
+};
+
+use std::str::from_utf8;
+use tracing::debug;
+
+/// These are options to modify the behaviour of composer depends
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerWhyOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+    /// The output format to ask composer for, by default it is chosen based
+    /// on the composer version (see [DependsFormat::for_version]) if the
+    /// semver feature is enabled and [DependsFormat::Text] otherwise
+    #[clap(
+        long = "format",
+        value_name = "text|tree",
+        value_parser = parse_depends_format,
+        help = "Output format to ask composer depends for (defaults to the best one the composer version supports)"
+    )]
+    pub format: Option<DependsFormat>,
+}
+
+/// The output format of composer depends which is parsed, composer has no
+/// JSON output for depends so the tree is the most structured one, both
+/// produce the same [DependencyLink]s
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependsFormat {
+    /// The table composer prints by default, the columns are only separated
+    /// by whitespace so the version and relation have to be guessed
+    #[default]
+    Text,
+    /// The tree composer prints with `--tree`, every dependent is on its own
+    /// line with the relation and constraint in parentheses, only the direct
+    /// dependents at the top level of the tree are reported
+    Tree,
+}
+
+#[cfg(feature = "semver")]
+impl DependsFormat {
+    /// the best format the composer version supports, the tree format of
+    /// composer before 2.0 (which this crate does not support otherwise
+    /// either) did not include the constraints so the text is parsed there
+    pub fn for_version(version: &crate::ComposerVersion) -> Self {
+        if version.version >= semver::Version::new(2, 0, 0) {
+            DependsFormat::Tree
+        } else {
+            DependsFormat::Text
+        }
+    }
+}
+
+/// parses the format command line argument, either `text` or `tree`
+fn parse_depends_format(s: &str) -> Result<DependsFormat, String> {
+    match s {
+        "text" => Ok(DependsFormat::Text),
+        "tree" => Ok(DependsFormat::Tree),
+        _ => Err(format!("expected text or tree but got {}", s)),
+    }
+}
+
+/// A single reason why a package is installed, i.e. another package
+/// depending on it
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DependencyLink {
+    /// Name of the package depending on the package we asked about
+    pub dependent: String,
+    /// Version of the dependent package, composer does not report one for
+    /// the root package
+    pub version: Option<String>,
+    /// The kind of dependency, e.g. `requires` or `requires (for development)`
+    pub relation: String,
+    /// The version constraint the dependent package places on the package
+    pub constraint: String,
+}
+
+/// parses a line of the composer depends output in the form
+/// `monolog/monolog 3.5.0 requires psr/log (^2.0 || ^3.0)`
+fn parse_dependency_link(line: &str) -> Option<DependencyLink> {
+    let line = line.trim();
+    let (rest, constraint) = line.strip_suffix(')')?.rsplit_once(" (")?;
+    let mut words = rest.split_whitespace();
+    let dependent = words.next()?.to_string();
+    let mut words: Vec<&str> = words.collect();
+    // the last word is the name of the package we asked about
+    words.pop()?;
+    let version = match words.first() {
+        Some(&"-") => {
+            words.remove(0);
+            None
+        }
+        Some(word)
+            if word.chars().next().is_some_and(|c| c.is_ascii_digit())
+                || word.starts_with('v')
+                || word.starts_with("dev-") =>
+        {
+            let version = word.to_string();
+            words.remove(0);
+            Some(version)
+        }
+        _ => None,
+    };
+    if words.is_empty() {
+        return None;
+    }
+    Some(DependencyLink {
+        dependent,
+        version,
+        relation: words.join(" "),
+        constraint: constraint.to_string(),
+    })
+}
+
+/// parses the text output of composer depends, composer prohibits uses the
+/// same format
+pub(crate) fn parse_depends_output(output: &str) -> Vec<DependencyLink> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let link = parse_dependency_link(line);
+            if link.is_none() && !line.trim().is_empty() {
+                debug!(line, "ignoring unrecognized composer depends line");
+            }
+            link
+        })
+        .collect()
+}
+
+/// the branch composer draws in front of the direct dependents in the tree
+const TREE_BRANCHES: &[&str] = &["├──", "└──"];
+
+/// the warning composer appends to a line in the tree if the dependency
+/// chain is circular
+const CIRCULAR_WARNING: &str = "(circular dependency aborted here)";
+
+/// parses a top level line of the composer depends --tree output in the
+/// form `├──monolog/monolog 3.5.0 (requires psr/log ^2.0 || ^3.0)`, the
+/// package we asked about is needed to tell the relation, which can contain
+/// parentheses itself, from the constraint
+fn parse_tree_line(line: &str, package: &str) -> Option<DependencyLink> {
+    let line = TREE_BRANCHES
+        .iter()
+        .find_map(|branch| line.strip_prefix(branch))?;
+    let line = line
+        .trim_end()
+        .strip_suffix(CIRCULAR_WARNING)
+        .unwrap_or(line)
+        .trim_end();
+    let (dependent, rest) = line.split_once(' ')?;
+    let (version, link) = rest.split_once(" (")?;
+    let link = link.strip_suffix(')')?;
+    let target = format!(" {} ", package.to_lowercase());
+    let position = link.to_lowercase().find(&target)?;
+    let relation = link[..position].trim();
+    let constraint = link[position + target.len()..].trim();
+    if relation.is_empty() {
+        return None;
+    }
+    Some(DependencyLink {
+        dependent: dependent.to_string(),
+        version: Some(version.trim())
+            .filter(|version| !version.is_empty() && *version != "-")
+            .map(String::from),
+        relation: relation.to_string(),
+        constraint: constraint.to_string(),
+    })
+}
+
+/// parses the output of composer depends --tree, the first line describes
+/// the package we asked about and the indented lines below the top level
+/// ones are indirect dependents which the text output does not include
+/// either
+fn parse_depends_tree(output: &str, package: &str) -> Vec<DependencyLink> {
+    output
+        .lines()
+        .filter(|line| TREE_BRANCHES.iter().any(|branch| line.starts_with(branch)))
+        .filter_map(|line| {
+            let link = parse_tree_line(line, package);
+            if link.is_none() {
+                debug!(line, "ignoring unrecognized composer depends tree line");
+            }
+            link
+        })
+        .collect()
+}
+
+/// the format to use for the options, detecting it from the composer
+/// version if it is not set and that is possible
+fn depends_format(options: &ComposerWhyOptions) -> DependsFormat {
+    if let Some(format) = options.format {
+        return format;
+    }
+    #[cfg(feature = "semver")]
+    match crate::composer_version(&options.command) {
+        Ok(version) => return DependsFormat::for_version(&version),
+        Err(err) => {
+            debug!(error = %err, "could not determine composer version, parsing text output")
+        }
+    }
+    DependsFormat::Text
+}
+
+/// main entry point for the composer-depends call, returns the packages that
+/// depend on the given package
+pub fn why(package: &str, options: &ComposerWhyOptions) -> Result<Vec<DependencyLink>, Error> {
+    let format = depends_format(options);
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.arg("depends")
+        .args(plugin_args(&options.command))
+        .arg("--locked");
+    if format == DependsFormat::Tree {
+        cmd.arg("--tree");
+    }
+    cmd.arg(package);
+
+    let output = run_composer(cmd, &options.command)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // composer exits with an unsuccessful exit code if nothing depends
+        // on the package
+        if stderr.contains("There is no installed package depending on") {
+            return Ok(vec![]);
+        }
+        log_failed_call("depends", &output);
+        return Err(command_failed(&output));
+    }
+
+    let stdout = from_utf8(&output.stdout)?;
+    Ok(match format {
+        DependsFormat::Text => parse_depends_output(stdout),
+        DependsFormat::Tree => parse_depends_tree(stdout, package),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_depends_output() {
+        let output = "\
+acme/website     dev-main requires                   psr/log (^3.0)
+monolog/monolog  3.5.0    requires                   psr/log (^2.0 || ^3.0)
+phpunit/phpunit  10.5.5   requires (for development) psr/log (*)
+symfony/console  v6.4.1   conflicts                  psr/log (>=4)
+";
+        assert_eq!(
+            parse_depends_output(output),
+            vec![
+                DependencyLink {
+                    dependent: "acme/website".to_string(),
+                    version: Some("dev-main".to_string()),
+                    relation: "requires".to_string(),
+                    constraint: "^3.0".to_string(),
+                },
+                DependencyLink {
+                    dependent: "monolog/monolog".to_string(),
+                    version: Some("3.5.0".to_string()),
+                    relation: "requires".to_string(),
+                    constraint: "^2.0 || ^3.0".to_string(),
+                },
+                DependencyLink {
+                    dependent: "phpunit/phpunit".to_string(),
+                    version: Some("10.5.5".to_string()),
+                    relation: "requires (for development)".to_string(),
+                    constraint: "*".to_string(),
+                },
+                DependencyLink {
+                    dependent: "symfony/console".to_string(),
+                    version: Some("v6.4.1".to_string()),
+                    relation: "conflicts".to_string(),
+                    constraint: ">=4".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_depends_output_without_version() {
+        assert_eq!(
+            parse_depends_output("__root__ - requires psr/log (^3.0)\n"),
+            vec![DependencyLink {
+                dependent: "__root__".to_string(),
+                version: None,
+                relation: "requires".to_string(),
+                constraint: "^3.0".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_depends_tree() {
+        let output = "\
+psr/log 3.0.0 Common interface for logging libraries
+├──acme/website dev-main (requires psr/log ^3.0)
+├──monolog/monolog 3.5.0 (requires psr/log ^2.0 || ^3.0)
+│  └──acme/website dev-main (requires monolog/monolog ^3.0)
+├──phpunit/phpunit 10.5.5 (requires (for development) psr/log *)
+└──symfony/console v6.4.1 (conflicts psr/log >=4) (circular dependency aborted here)
+";
+        let text = "\
+acme/website     dev-main requires                   psr/log (^3.0)
+monolog/monolog  3.5.0    requires                   psr/log (^2.0 || ^3.0)
+phpunit/phpunit  10.5.5   requires (for development) psr/log (*)
+symfony/console  v6.4.1   conflicts                  psr/log (>=4)
+";
+        assert_eq!(
+            parse_depends_tree(output, "psr/log"),
+            parse_depends_output(text)
+        );
+    }
+
+    #[test]
+    fn test_parse_depends_tree_platform_package() {
+        assert_eq!(
+            parse_depends_tree(
+                "php 8.3.1 The PHP interpreter\n└──__root__ - (requires php >=8.1)\n",
+                "php"
+            ),
+            vec![DependencyLink {
+                dependent: "__root__".to_string(),
+                version: None,
+                relation: "requires".to_string(),
+                constraint: ">=8.1".to_string(),
+            }]
+        );
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn test_depends_format_for_version() {
+        let version = |version| crate::ComposerVersion {
+            version: semver::Version::new(version, 0, 0),
+        };
+        assert_eq!(DependsFormat::for_version(&version(2)), DependsFormat::Tree);
+        assert_eq!(DependsFormat::for_version(&version(1)), DependsFormat::Text);
+    }
+
+    #[test]
+    fn test_parse_depends_output_empty() {
+        assert_eq!(parse_depends_output(""), vec![]);
+    }
+}
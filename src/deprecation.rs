@@ -0,0 +1,98 @@
+//! Recognition of the deprecation notices composer prints on stderr
+
+use crate::ComposerOutdatedRun;
+
+/// the prefixes of the lines PHP and composer use for deprecation notices
+const DEPRECATION_PREFIXES: &[&str] = &["Deprecation Notice:", "PHP Deprecated:", "Deprecated:"];
+
+/// A deprecation notice composer printed on stderr
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Deprecation {
+    /// The notice without the prefix, e.g. `Return type of ... should
+    /// either be compatible with ... in /project/vendor/... on line 12`
+    pub message: String,
+    /// The package the deprecated code is in, derived from a path below
+    /// `vendor/` in the message, None for notices about code outside of
+    /// vendor, e.g. in composer itself or the dependencies bundled in the
+    /// composer phar (`phar://.../vendor/...`)
+    pub package: Option<String>,
+}
+
+/// the package of the first path below a vendor directory in the message,
+/// paths inside a phar are skipped since the vendor directory in the
+/// composer phar holds the dependencies of composer, not of the project
+fn package_from_message(message: &str) -> Option<String> {
+    message
+        .split_whitespace()
+        .filter(|word| !word.starts_with("phar://"))
+        .find_map(|path| {
+            let (_, rest) = path.split_once("/vendor/")?;
+            let mut components = rest.split('/');
+            let vendor = components.next().filter(|c| !c.is_empty())?;
+            let name = components.next().filter(|c| !c.is_empty())?;
+            Some(format!("{}/{}", vendor, name))
+        })
+}
+
+/// parses the deprecation notices PHP and composer print to stderr
+/// (`Deprecated: ...`, `PHP Deprecated: ...` and composer's own
+/// `Deprecation Notice: ...`), all other lines are ignored
+pub fn parse_deprecations(stderr: &str) -> Vec<Deprecation> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let message = DEPRECATION_PREFIXES
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))?
+                .trim();
+            Some(Deprecation {
+                message: message.to_string(),
+                package: package_from_message(message),
+            })
+        })
+        .collect()
+}
+
+impl ComposerOutdatedRun {
+    /// the deprecation notices in the output of composer on stderr, see
+    /// [parse_deprecations], the full output is still available in
+    /// [stderr](ComposerOutdatedRun::stderr)
+    pub fn deprecations(&self) -> Vec<Deprecation> {
+        parse_deprecations(&self.stderr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_deprecations() {
+        let stderr = "\
+Deprecation Notice: Return type of Symfony\\Component\\Console\\Helper\\HelperSet::getIterator() should either be compatible with IteratorAggregate::getIterator(): Traversable in phar:///usr/local/bin/composer/vendor/symfony/console/Helper/HelperSet.php:110
+PHP Deprecated:  Creation of dynamic property Acme\\Plugin::$io is deprecated in /srv/project/vendor/acme/composer-plugin/src/Plugin.php on line 23
+Deprecated: Implicit conversion from float to int loses precision in /srv/project/src/Legacy.php on line 7
+Info from https://repo.packagist.org: #StandWithUkraine
+";
+        assert_eq!(
+            parse_deprecations(stderr),
+            vec![
+                Deprecation {
+                    message: "Return type of Symfony\\Component\\Console\\Helper\\HelperSet::getIterator() should either be compatible with IteratorAggregate::getIterator(): Traversable in phar:///usr/local/bin/composer/vendor/symfony/console/Helper/HelperSet.php:110".to_string(),
+                    package: None,
+                },
+                Deprecation {
+                    message: "Creation of dynamic property Acme\\Plugin::$io is deprecated in /srv/project/vendor/acme/composer-plugin/src/Plugin.php on line 23".to_string(),
+                    package: Some("acme/composer-plugin".to_string()),
+                },
+                Deprecation {
+                    message: "Implicit conversion from float to int loses precision in /srv/project/src/Legacy.php on line 7".to_string(),
+                    package: None,
+                },
+            ]
+        );
+        assert_eq!(parse_deprecations(""), vec![]);
+    }
+}
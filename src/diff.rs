@@ -0,0 +1,160 @@
+//! Comparison of two composer outdated runs, e.g. to report dependency drift
+
+use crate::{ComposerOutdatedData, PackageKey, PackageStatus};
+
+use std::collections::BTreeMap;
+
+/// The differences between two [ComposerOutdatedData] snapshots, packages are
+/// matched by name and every list is sorted by name
+#[derive(Debug, serde::Serialize)]
+pub struct OutdatedDiff<'a> {
+    /// Packages which are only part of the current snapshot
+    pub added: Vec<&'a PackageStatus>,
+    /// Packages which are only part of the previous snapshot
+    pub removed: Vec<&'a PackageStatus>,
+    /// Packages whose version in use changed
+    pub version_changed: Vec<PackageChange<'a>>,
+    /// Packages whose [latest_status](PackageStatus::latest_status) changed
+    pub status_changed: Vec<PackageChange<'a>>,
+}
+
+impl OutdatedDiff<'_> {
+    /// are both snapshots the same as far as this diff is concerned
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.version_changed.is_empty()
+            && self.status_changed.is_empty()
+    }
+}
+
+/// A package which is part of both snapshots
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct PackageChange<'a> {
+    /// The package in the previous snapshot
+    pub previous: &'a PackageStatus,
+    /// The package in the current snapshot
+    pub current: &'a PackageStatus,
+}
+
+/// the packages of a snapshot keyed by name
+fn by_name(data: &ComposerOutdatedData) -> BTreeMap<PackageKey<'_>, &PackageStatus> {
+    data.locked.iter().map(|p| (p.key(), p)).collect()
+}
+
+impl ComposerOutdatedData {
+    /// compares this snapshot with a previous one
+    pub fn diff<'a>(&'a self, previous: &'a ComposerOutdatedData) -> OutdatedDiff<'a> {
+        let current_packages = by_name(self);
+        let previous_packages = by_name(previous);
+        let mut diff = OutdatedDiff {
+            added: vec![],
+            removed: vec![],
+            version_changed: vec![],
+            status_changed: vec![],
+        };
+        for (name, current) in &current_packages {
+            let Some(previous) = previous_packages.get(name) else {
+                diff.added.push(current);
+                continue;
+            };
+            let change = PackageChange { previous, current };
+            if previous.version != current.version {
+                diff.version_changed.push(change);
+            }
+            if previous.latest_status != current.latest_status {
+                diff.status_changed.push(change);
+            }
+        }
+        diff.removed = previous_packages
+            .iter()
+            .filter(|(name, _)| !current_packages.contains_key(*name))
+            .map(|(_, p)| *p)
+            .collect();
+        diff
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::UpdateRequirement;
+    use pretty_assertions::assert_eq;
+
+    /// parses outdated data from a list of name, version and status
+    fn data(packages: &[(&str, &str, &str)]) -> ComposerOutdatedData {
+        let locked: Vec<serde_json::Value> = packages
+            .iter()
+            .map(|(name, version, status)| {
+                serde_json::json!({
+                    "name": name,
+                    "version": version,
+                    "latest": "9.9.9",
+                    "latest-status": status,
+                    "description": "",
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({ "locked": locked }))
+            .expect("sample data should parse")
+    }
+
+    /// the names of the packages
+    fn names(packages: &[&PackageStatus]) -> Vec<String> {
+        packages.iter().map(|p| p.name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = data(&[
+            ("psr/log", "3.0.0", "up-to-date"),
+            ("monolog/monolog", "3.4.0", "semver-safe-update"),
+            ("swiftmailer/swiftmailer", "6.3.0", "up-to-date"),
+        ]);
+        let current = data(&[
+            ("monolog/monolog", "3.5.0", "up-to-date"),
+            ("psr/log", "3.0.0", "update-possible"),
+            ("symfony/mailer", "7.0.0", "up-to-date"),
+        ]);
+        let diff = current.diff(&previous);
+        assert_eq!(names(&diff.added), vec!["symfony/mailer"]);
+        assert_eq!(names(&diff.removed), vec!["swiftmailer/swiftmailer"]);
+        assert_eq!(diff.version_changed.len(), 1);
+        assert_eq!(diff.version_changed[0].previous.version, "3.4.0");
+        assert_eq!(diff.version_changed[0].current.version, "3.5.0");
+        let status_changes: Vec<(&str, &UpdateRequirement, &UpdateRequirement)> = diff
+            .status_changed
+            .iter()
+            .map(|c| {
+                (
+                    c.current.name.as_str(),
+                    &c.previous.latest_status,
+                    &c.current.latest_status,
+                )
+            })
+            .collect();
+        assert_eq!(
+            status_changes,
+            vec![
+                (
+                    "monolog/monolog",
+                    &UpdateRequirement::SemverSafeUpdate,
+                    &UpdateRequirement::UpToDate
+                ),
+                (
+                    "psr/log",
+                    &UpdateRequirement::UpToDate,
+                    &UpdateRequirement::UpdatePossible
+                ),
+            ]
+        );
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let previous = data(&[("psr/log", "3.0.0", "up-to-date")]);
+        let current = data(&[("psr/log", "3.0.0", "up-to-date")]);
+        assert!(current.diff(&previous).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Classification of failed composer calls by the messages on stderr
+
+use crate::Error;
+
+use std::process::ExitStatus;
+
+/// The reasons for a failed composer call we can recognize on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// composer.lock does not match composer.json
+    LockOutOfDate,
+    /// the PHP version or an extension required by a package is missing
+    PlatformRequirementFailed,
+    /// composer install has not been run
+    VendorNotInstalled,
+}
+
+/// messages composer prints on stderr and the failure they indicate, the
+/// first match wins so more specific messages need to come first, matching
+/// is case-insensitive
+const FAILURE_MARKERS: &[(&str, FailureKind)] = &[
+    ("no dependencies installed", FailureKind::VendorNotInstalled),
+    (
+        "vendor/autoload.php could not be found",
+        FailureKind::VendorNotInstalled,
+    ),
+    (
+        "composer detected issues in your platform",
+        FailureKind::PlatformRequirementFailed,
+    ),
+    (
+        "is missing from your system",
+        FailureKind::PlatformRequirementFailed,
+    ),
+    (
+        "does not satisfy that requirement",
+        FailureKind::PlatformRequirementFailed,
+    ),
+    ("lock file is not up to date", FailureKind::LockOutOfDate),
+];
+
+/// finds the first known failure message in the output on stderr
+fn classify(stderr: &str) -> Option<FailureKind> {
+    let stderr = stderr.to_lowercase();
+    FAILURE_MARKERS
+        .iter()
+        .find(|(marker, _)| stderr.contains(marker))
+        .map(|(_, kind)| *kind)
+}
+
+/// the error for a failed composer call, a specific variant if the reason
+/// could be recognized on stderr or [Error::CommandFailed] otherwise
+pub(crate) fn classify_failure(status: ExitStatus, stderr: String) -> Error {
+    match classify(&stderr) {
+        Some(FailureKind::LockOutOfDate) => Error::LockOutOfDate { status, stderr },
+        Some(FailureKind::PlatformRequirementFailed) => {
+            Error::PlatformRequirementFailed { status, stderr }
+        }
+        Some(FailureKind::VendorNotInstalled) => Error::VendorNotInstalled { status, stderr },
+        None => Error::CommandFailed { status, stderr },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_classify_lock_out_of_date() {
+        assert_eq!(
+            classify(
+                "Warning: The lock file is not up to date with the latest changes in composer.json. You may be getting outdated dependencies. It is recommended that you run `composer update` or `composer update <package name>`."
+            ),
+            Some(FailureKind::LockOutOfDate)
+        );
+    }
+
+    #[test]
+    fn test_classify_platform_requirement_failed() {
+        assert_eq!(
+            classify(
+                "Your lock file does not contain a compatible set of packages. Please run composer update.
+
+  Problem 1
+    - symfony/console is locked to version v7.1.0 and an update of this package was not requested.
+    - symfony/console v7.1.0 requires php >=8.2 -> your php version (8.1.2) does not satisfy that requirement."
+            ),
+            Some(FailureKind::PlatformRequirementFailed)
+        );
+        assert_eq!(
+            classify(
+                "    - Root composer.json requires PHP extension ext-intl * but it is missing from your system. Install or enable PHP's intl extension."
+            ),
+            Some(FailureKind::PlatformRequirementFailed)
+        );
+        assert_eq!(
+            classify("Composer detected issues in your platform: Your Composer dependencies require a PHP version \">= 8.2.0\"."),
+            Some(FailureKind::PlatformRequirementFailed)
+        );
+    }
+
+    #[test]
+    fn test_classify_vendor_not_installed() {
+        assert_eq!(
+            classify("No dependencies installed. Try running composer install or update."),
+            Some(FailureKind::VendorNotInstalled)
+        );
+    }
+
+    #[test]
+    fn test_classify_unknown() {
+        assert_eq!(classify(""), None);
+        assert_eq!(classify("The \"--foo\" option does not exist."), None);
+    }
+
+    #[test]
+    fn test_classify_prefers_specific_failures() {
+        assert_eq!(
+            classify(
+                "Warning: The lock file is not up to date with the latest changes in composer.json.
+No dependencies installed. Try running composer install or update."
+            ),
+            Some(FailureKind::VendorNotInstalled)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_classify_failure() {
+        use std::os::unix::process::ExitStatusExt as _;
+
+        let status = ExitStatus::from_raw(1 << 8);
+        assert!(matches!(
+            classify_failure(status, "No dependencies installed.".to_string()),
+            Error::VendorNotInstalled { .. }
+        ));
+        assert!(matches!(
+            classify_failure(status, "something else".to_string()),
+            Error::CommandFailed { stderr, .. } if stderr == "something else"
+        ));
+    }
+}
@@ -0,0 +1,439 @@
+//! Output formats for the parsed composer outdated data, e.g. for reports
+
+use crate::{ComposerOutdatedData, Error, UpdateRequirement};
+
+/// escapes a value for use in a Markdown table cell
+fn escape_markdown_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace('\n', " ")
+}
+
+/// escapes the message of a GitHub Actions workflow command
+fn escape_annotation_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// escapes a property value of a GitHub Actions workflow command
+fn escape_annotation_property(value: &str) -> String {
+    escape_annotation_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+impl ComposerOutdatedData {
+    /// renders the packages as a Markdown table with the columns Name,
+    /// Current, Latest and Status, the most urgent updates first (see
+    /// [sorted_by_severity](ComposerOutdatedData::sorted_by_severity)), up to
+    /// date packages are only included if requested
+    pub fn to_markdown_table(&self, include_up_to_date: bool) -> String {
+        let mut table = String::from("| Name | Current | Latest | Status |\n");
+        table.push_str("| --- | --- | --- | --- |\n");
+        for package in self.sorted_by_severity() {
+            if !include_up_to_date && package.latest_status == UpdateRequirement::UpToDate {
+                continue;
+            }
+            table.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                escape_markdown_cell(package.name.as_str()),
+                escape_markdown_cell(&package.version),
+                escape_markdown_cell(&package.latest),
+                package.latest_status,
+            ));
+        }
+        table
+    }
+
+    /// renders the packages as GitHub Actions workflow commands so they show
+    /// up as annotations in the checks of a pull request, semver-safe
+    /// updates are warnings, other updates and abandoned packages are errors
+    /// and up to date packages are left out, every line is terminated by a
+    /// newline
+    pub fn to_github_annotations(&self) -> String {
+        let mut annotations = String::new();
+        for package in &self.locked {
+            let level = if package.is_abandoned()
+                || package.latest_status == UpdateRequirement::UpdatePossible
+            {
+                "error"
+            } else if package.latest_status == UpdateRequirement::SemverSafeUpdate {
+                "warning"
+            } else {
+                continue;
+            };
+            let mut message = if package.latest_status == UpdateRequirement::UpToDate {
+                format!("{} {} is up to date", package.name, package.version)
+            } else {
+                format!(
+                    "{} {} -> {} ({})",
+                    package.name, package.version, package.latest, package.latest_status
+                )
+            };
+            if let Some(warning) = package
+                .warning
+                .as_deref()
+                .filter(|_| package.is_abandoned())
+            {
+                message.push_str(": ");
+                message.push_str(warning);
+            }
+            annotations.push_str(&format!(
+                "::{} title={}::{}\n",
+                level,
+                escape_annotation_property(package.name.as_str()),
+                escape_annotation_data(&message),
+            ));
+        }
+        annotations
+    }
+
+    /// renders the packages as a CycloneDX 1.5 BOM which OWASP
+    /// Dependency-Track and similar tools can ingest, every package becomes a
+    /// library component with its vendor as group and a `pkg:composer` purl,
+    /// the latest version and whether the package is outdated are added as
+    /// properties (`composer:latest-version`, `composer:latest-status` and
+    /// `composer:outdated`) since CycloneDX has no fields for them
+    ///
+    /// Platform packages like `php` are left out since they are not
+    /// components, the description is only included if composer reported a
+    /// non-empty one and no hashes or licenses are included since composer
+    /// outdated does not report them
+    pub fn to_dependency_track(&self) -> serde_json::Value {
+        let components: Vec<serde_json::Value> = self
+            .locked
+            .iter()
+            .filter(|package| !package.name.is_platform())
+            .map(|package| {
+                let name = package
+                    .name
+                    .as_str()
+                    .split_once('/')
+                    .map_or(package.name.as_str(), |(_, name)| name);
+                let mut component = serde_json::json!({
+                    "type": "library",
+                    "bom-ref": format!("{}@{}", package.name, package.version),
+                    "group": package.name.vendor(),
+                    "name": name,
+                    "version": package.version,
+                    "purl": format!("pkg:composer/{}@{}", package.name, package.version),
+                    "properties": [
+                        { "name": "composer:latest-version", "value": package.latest },
+                        {
+                            "name": "composer:latest-status",
+                            "value": package.latest_status.to_string(),
+                        },
+                        {
+                            "name": "composer:outdated",
+                            "value": (package.latest_status != UpdateRequirement::UpToDate)
+                                .to_string(),
+                        },
+                    ],
+                });
+                if !package.description.is_empty() {
+                    component["description"] = package.description.as_str().into();
+                }
+                component
+            })
+            .collect();
+        serde_json::json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": components,
+        })
+    }
+
+    /// the data as a generic JSON value in the same shape it is serialized
+    /// in, e.g. to add fields before serializing it together with other JSON
+    pub fn to_json_value(&self) -> Result<serde_json::Value, Error> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// the data serialized as JSON, indented over multiple lines if pretty is
+    /// set, the fields are always in the same order so the output of two
+    /// runs can be compared with diff
+    pub fn to_json_string(&self, pretty: bool) -> Result<String, Error> {
+        if pretty {
+            Ok(serde_json::to_string_pretty(self)?)
+        } else {
+            Ok(serde_json::to_string(self)?)
+        }
+    }
+
+    /// writes the packages as newline-delimited JSON, one package per line,
+    /// every line including the last one is terminated by a newline and no
+    /// packages produce no output at all
+    pub fn to_ndjson<W: std::io::Write>(&self, mut w: W) -> Result<(), Error> {
+        for package in &self.locked {
+            serde_json::to_writer(&mut w, package)?;
+            w.write_all(b"\n")?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// writes the packages as CSV with a header row and one row per package
+    /// in the order of
+    /// [sorted_by_severity](ComposerOutdatedData::sorted_by_severity), the
+    /// status is written in the same kebab-case form as its Display
+    #[cfg(feature = "csv")]
+    pub fn to_csv<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(w);
+        writer.write_record([
+            "name",
+            "version",
+            "latest",
+            "latest-status",
+            "description",
+            "warning",
+        ])?;
+        for package in self.sorted_by_severity() {
+            writer.write_record([
+                package.name.as_str(),
+                package.version.as_str(),
+                package.latest.as_str(),
+                &package.latest_status.to_string(),
+                package.description.as_str(),
+                package.warning.as_deref().unwrap_or(""),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// outdated data with one up to date and one outdated package, shared
+    /// with the tests of the other output formats
+    pub(crate) fn sample_data() -> ComposerOutdatedData {
+        serde_json::from_str(
+            r#"{
+                "locked": [
+                    {
+                        "name": "monolog/monolog",
+                        "version": "3.4.0",
+                        "latest": "3.5.0",
+                        "latest-status": "semver-safe-update",
+                        "description": "Sends your logs to files, sockets | inboxes"
+                    },
+                    {
+                        "name": "psr/log",
+                        "version": "3.0.0",
+                        "latest": "3.0.0",
+                        "latest-status": "up-to-date",
+                        "description": "Common interface for logging libraries"
+                    }
+                ]
+            }"#,
+        )
+        .expect("sample data should parse")
+    }
+
+    #[test]
+    fn test_to_markdown_table() {
+        let data = sample_data();
+        assert_eq!(
+            data.to_markdown_table(true),
+            "| Name | Current | Latest | Status |\n\
+             | --- | --- | --- | --- |\n\
+             | monolog/monolog | 3.4.0 | 3.5.0 | semver-safe-update |\n\
+             | psr/log | 3.0.0 | 3.0.0 | up-to-date |\n"
+        );
+        assert_eq!(
+            data.to_markdown_table(false),
+            "| Name | Current | Latest | Status |\n\
+             | --- | --- | --- | --- |\n\
+             | monolog/monolog | 3.4.0 | 3.5.0 | semver-safe-update |\n"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_table_sorted_by_severity() {
+        let mut data = sample_data();
+        data.locked.reverse();
+        assert_eq!(
+            data.to_markdown_table(true),
+            sample_data().to_markdown_table(true)
+        );
+    }
+
+    #[test]
+    fn test_to_github_annotations() {
+        let mut data = sample_data();
+        data.locked.push(
+            serde_json::from_str(
+                r#"{
+                    "name": "swiftmailer/swiftmailer",
+                    "version": "6.3.0",
+                    "latest": "6.3.0",
+                    "latest-status": "up-to-date",
+                    "description": "Swiftmailer, free feature-rich PHP mailer",
+                    "warning": "Package swiftmailer/swiftmailer is abandoned, you should avoid using it. Use symfony/mailer instead."
+                }"#,
+            )
+            .expect("abandoned package should parse"),
+        );
+        assert_eq!(
+            data.to_github_annotations(),
+            "::warning title=monolog/monolog::monolog/monolog 3.4.0 -> 3.5.0 (semver-safe-update)\n\
+             ::error title=swiftmailer/swiftmailer::swiftmailer/swiftmailer 6.3.0 is up to date: Package swiftmailer/swiftmailer is abandoned, you should avoid using it. Use symfony/mailer instead.\n"
+        );
+        assert_eq!(
+            ComposerOutdatedData { locked: vec![] }.to_github_annotations(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_escape_annotation() {
+        assert_eq!(
+            escape_annotation_data("100%\r\nnext, line: 2"),
+            "100%25%0D%0Anext, line: 2"
+        );
+        assert_eq!(escape_annotation_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn test_to_ndjson() -> Result<(), Error> {
+        let mut buf = Vec::new();
+        sample_data().to_ndjson(&mut buf)?;
+        let ndjson = String::from_utf8(buf).expect("NDJSON should be valid UTF-8");
+        assert!(ndjson.ends_with('\n'));
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["name"], "monolog/monolog");
+        assert_eq!(lines[1]["latest-status"], "up-to-date");
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_dependency_track() {
+        let mut data = sample_data();
+        data.locked[1].description = String::new();
+        data.locked.push(
+            serde_json::from_str(
+                r#"{
+                    "name": "php",
+                    "version": "8.3.12",
+                    "latest": "8.3.12",
+                    "latest-status": "up-to-date",
+                    "description": ""
+                }"#,
+            )
+            .expect("platform package should parse"),
+        );
+        assert_eq!(
+            data.to_dependency_track(),
+            serde_json::json!({
+                "bomFormat": "CycloneDX",
+                "specVersion": "1.5",
+                "version": 1,
+                "components": [
+                    {
+                        "type": "library",
+                        "bom-ref": "monolog/monolog@3.4.0",
+                        "group": "monolog",
+                        "name": "monolog",
+                        "version": "3.4.0",
+                        "purl": "pkg:composer/monolog/monolog@3.4.0",
+                        "description": "Sends your logs to files, sockets | inboxes",
+                        "properties": [
+                            { "name": "composer:latest-version", "value": "3.5.0" },
+                            { "name": "composer:latest-status", "value": "semver-safe-update" },
+                            { "name": "composer:outdated", "value": "true" },
+                        ],
+                    },
+                    {
+                        "type": "library",
+                        "bom-ref": "psr/log@3.0.0",
+                        "group": "psr",
+                        "name": "log",
+                        "version": "3.0.0",
+                        "purl": "pkg:composer/psr/log@3.0.0",
+                        "properties": [
+                            { "name": "composer:latest-version", "value": "3.0.0" },
+                            { "name": "composer:latest-status", "value": "up-to-date" },
+                            { "name": "composer:outdated", "value": "false" },
+                        ],
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_json_value() -> Result<(), Error> {
+        let value = sample_data().to_json_value()?;
+        assert_eq!(value["locked"][0]["name"], "monolog/monolog");
+        assert_eq!(value["locked"][1]["latest-status"], "up-to-date");
+        assert_eq!(value, serde_json::to_value(sample_data())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_json_string() -> Result<(), Error> {
+        let data = ComposerOutdatedData {
+            locked: vec![sample_data().locked.remove(1)],
+        };
+        assert_eq!(
+            data.to_json_string(false)?,
+            r#"{"locked":[{"name":"psr/log","version":"3.0.0","latest":"3.0.0","latest-status":"up-to-date","description":"Common interface for logging libraries","warning":null}]}"#
+        );
+        assert_eq!(
+            data.to_json_string(true)?,
+            r#"{
+  "locked": [
+    {
+      "name": "psr/log",
+      "version": "3.0.0",
+      "latest": "3.0.0",
+      "latest-status": "up-to-date",
+      "description": "Common interface for logging libraries",
+      "warning": null
+    }
+  ]
+}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_ndjson_without_packages() -> Result<(), Error> {
+        let mut buf = Vec::new();
+        ComposerOutdatedData { locked: vec![] }.to_ndjson(&mut buf)?;
+        assert!(buf.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_to_csv() -> Result<(), Error> {
+        let mut data = sample_data();
+        data.locked[1].warning = Some("first line, with comma\nsecond \"line\"".to_string());
+        let mut buf = Vec::new();
+        data.to_csv(&mut buf)?;
+        assert_eq!(
+            String::from_utf8(buf).expect("CSV should be valid UTF-8"),
+            "name,version,latest,latest-status,description,warning\n\
+             monolog/monolog,3.4.0,3.5.0,semver-safe-update,\"Sends your logs to files, sockets | inboxes\",\n\
+             psr/log,3.0.0,3.0.0,up-to-date,Common interface for logging libraries,\"first line, with comma\nsecond \"\"line\"\"\"\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape_markdown_cell() {
+        assert_eq!(escape_markdown_cell("a | b\nc"), "a \\| b c");
+    }
+}
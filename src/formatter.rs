@@ -0,0 +1,203 @@
+//! Pluggable formatters writing the parsed composer outdated data in one of
+//! the output formats
+
+use crate::{ComposerOutdatedData, Error};
+
+use std::io::Write;
+
+/// Writes composer outdated data in some output format, implement this to
+/// add a format of your own, the built-in ones are [MarkdownFormatter],
+/// [JsonFormatter], [NdjsonFormatter] and, with the csv feature,
+/// [CsvFormatter], [OutputFormat] selects one of them by name
+pub trait OutdatedFormatter {
+    /// writes the data to the writer
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error>;
+}
+
+/// Writes the Markdown table of
+/// [to_markdown_table](ComposerOutdatedData::to_markdown_table)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarkdownFormatter {
+    /// Include the packages which are up to date
+    pub include_up_to_date: bool,
+}
+
+impl OutdatedFormatter for MarkdownFormatter {
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+        w.write_all(data.to_markdown_table(self.include_up_to_date).as_bytes())?;
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes the JSON of [to_json_string](ComposerOutdatedData::to_json_string)
+/// followed by a newline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonFormatter {
+    /// Indent the JSON over multiple lines
+    pub pretty: bool,
+}
+
+impl OutdatedFormatter for JsonFormatter {
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+        w.write_all(data.to_json_string(self.pretty)?.as_bytes())?;
+        w.write_all(b"\n")?;
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes the newline-delimited JSON of
+/// [to_ndjson](ComposerOutdatedData::to_ndjson)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NdjsonFormatter;
+
+impl OutdatedFormatter for NdjsonFormatter {
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+        data.to_ndjson(w)
+    }
+}
+
+/// Writes the CSV of [to_csv](ComposerOutdatedData::to_csv)
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvFormatter;
+
+#[cfg(feature = "csv")]
+impl OutdatedFormatter for CsvFormatter {
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+        data.to_csv(w)
+    }
+}
+
+/// The built-in output formats by name, e.g. to choose one with a command
+/// line argument, Markdown leaves out up to date packages and JSON is
+/// written compactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// [MarkdownFormatter]
+    Markdown,
+    /// [JsonFormatter]
+    Json,
+    /// [NdjsonFormatter]
+    Ndjson,
+    /// [CsvFormatter]
+    #[cfg(feature = "csv")]
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(OutputFormat::Markdown),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            #[cfg(feature = "csv")]
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("unknown output format {}", s)),
+        }
+    }
+}
+
+impl OutdatedFormatter for OutputFormat {
+    fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+        match self {
+            OutputFormat::Markdown => MarkdownFormatter::default().format(data, w),
+            OutputFormat::Json => JsonFormatter::default().format(data, w),
+            OutputFormat::Ndjson => NdjsonFormatter.format(data, w),
+            #[cfg(feature = "csv")]
+            OutputFormat::Csv => CsvFormatter.format(data, w),
+        }
+    }
+}
+
+impl ComposerOutdatedData {
+    /// writes the data with the given formatter
+    ///
+    /// ```
+    /// use composer_parser::{parse_outdated_json, JsonFormatter};
+    ///
+    /// let data = parse_outdated_json(r#"{"locked": []}"#)?;
+    /// let mut output = vec![];
+    /// data.format_with(&JsonFormatter::default(), &mut output)?;
+    /// assert_eq!(output, b"{\"locked\":[]}\n");
+    /// # Ok::<(), composer_parser::Error>(())
+    /// ```
+    pub fn format_with(
+        &self,
+        formatter: &dyn OutdatedFormatter,
+        w: &mut dyn Write,
+    ) -> Result<(), Error> {
+        formatter.format(self, w)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::format::test::sample_data;
+    use pretty_assertions::assert_eq;
+
+    /// a formatter outside of the built-in ones writing only the names
+    struct NamesFormatter;
+
+    impl OutdatedFormatter for NamesFormatter {
+        fn format(&self, data: &ComposerOutdatedData, w: &mut dyn Write) -> Result<(), Error> {
+            for package in &data.locked {
+                writeln!(w, "{}", package.name)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// the output of the formatter for the sample data
+    fn formatted(formatter: &dyn OutdatedFormatter) -> Result<String, Error> {
+        let mut output = vec![];
+        sample_data().format_with(formatter, &mut output)?;
+        Ok(String::from_utf8(output).expect("output should be UTF-8"))
+    }
+
+    #[test]
+    fn test_built_in_formatters() -> Result<(), Error> {
+        let data = sample_data();
+        assert_eq!(
+            formatted(&MarkdownFormatter::default())?,
+            data.to_markdown_table(false)
+        );
+        assert_eq!(
+            formatted(&OutputFormat::Markdown)?,
+            data.to_markdown_table(false)
+        );
+        assert_eq!(
+            formatted(&JsonFormatter { pretty: true })?,
+            format!("{}\n", data.to_json_string(true)?)
+        );
+        let mut ndjson = vec![];
+        data.to_ndjson(&mut ndjson)?;
+        assert_eq!(formatted(&OutputFormat::Ndjson)?.as_bytes(), ndjson);
+        #[cfg(feature = "csv")]
+        {
+            let mut csv = vec![];
+            data.to_csv(&mut csv)?;
+            assert_eq!(formatted(&CsvFormatter)?.as_bytes(), csv);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_formatter() -> Result<(), Error> {
+        assert_eq!(formatted(&NamesFormatter)?, "monolog/monolog\npsr/log\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!(
+            "xml".parse::<OutputFormat>(),
+            Err("unknown output format xml".to_string())
+        );
+    }
+}
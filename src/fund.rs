@@ -0,0 +1,168 @@
+//! Support for parsing the JSON output of composer fund
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, composer_command, log_failed_call, run_composer, ComposerCommandOptions, Error,
+};
+
+use std::collections::BTreeMap;
+use std::str::from_utf8;
+use tracing::warn;
+
+/// These are options to modify the behaviour of composer fund
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerFundOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// The funding links of a single package
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageFunding {
+    /// Package name
+    pub name: String,
+    /// The ways to fund the package
+    pub funding: Vec<FundingLink>,
+}
+
+/// A single way to fund a package
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FundingLink {
+    /// The kind of funding, e.g. `github` or `custom`, composer fund does not
+    /// include this in its JSON output so it is only known if the link was
+    /// read from somewhere else
+    #[serde(rename = "type")]
+    pub funding_type: Option<String>,
+    /// The URL to fund the package at
+    pub url: String,
+}
+
+/// The shape of the composer fund output, composer groups the packages by
+/// vendor and funding URL and PHP encodes an empty result as an empty list
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawFundings {
+    /// no package advertises funding, composer only uses a list when it is
+    /// empty
+    List(Vec<serde_json::Value>),
+    /// package names (without vendor) keyed by funding URL keyed by vendor
+    ByVendor(BTreeMap<String, BTreeMap<String, Vec<String>>>),
+}
+
+/// parses the JSON output of composer fund into a list of packages sorted by
+/// name
+fn parse_fund_output(json: &str) -> Result<Vec<PackageFunding>, Error> {
+    let raw: RawFundings = serde_json::from_str(json)?;
+    let by_vendor = match raw {
+        RawFundings::List(values) => {
+            if !values.is_empty() {
+                warn!("ignoring unexpected non-empty list in composer fund output");
+            }
+            return Ok(vec![]);
+        }
+        RawFundings::ByVendor(by_vendor) => by_vendor,
+    };
+    let mut by_package: BTreeMap<String, Vec<FundingLink>> = BTreeMap::new();
+    for (vendor, by_url) in by_vendor {
+        for (url, packages) in by_url {
+            for package in packages {
+                by_package
+                    .entry(format!("{}/{}", vendor, package))
+                    .or_default()
+                    .push(FundingLink {
+                        funding_type: None,
+                        url: url.to_owned(),
+                    });
+            }
+        }
+    }
+    Ok(by_package
+        .into_iter()
+        .map(|(name, funding)| PackageFunding { name, funding })
+        .collect())
+}
+
+/// main entry point for the composer-fund call
+pub fn fund(options: &ComposerFundOptions) -> Result<Vec<PackageFunding>, Error> {
+    let mut cmd = composer_command(&options.command)?;
+
+    cmd.args(["fund", "--format=json"])
+        .args(plugin_args(&options.command));
+
+    let output = run_composer(cmd, &options.command)?;
+
+    if !output.status.success() {
+        log_failed_call("fund", &output);
+        return Err(command_failed(&output));
+    }
+
+    parse_fund_output(from_utf8(&output.stdout)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_fund_output() -> Result<(), Error> {
+        let json = r#"{
+            "symfony": {
+                "https://symfony.com/sponsor": [
+                    "console",
+                    "process"
+                ],
+                "https://github.com/sponsors/fabpot": [
+                    "console"
+                ]
+            },
+            "sebastianbergmann": {
+                "https://github.com/sponsors/sebastianbergmann": [
+                    "phpunit"
+                ]
+            }
+        }"#;
+        assert_eq!(
+            parse_fund_output(json)?,
+            vec![
+                PackageFunding {
+                    name: "sebastianbergmann/phpunit".to_string(),
+                    funding: vec![FundingLink {
+                        funding_type: None,
+                        url: "https://github.com/sponsors/sebastianbergmann".to_string(),
+                    }],
+                },
+                PackageFunding {
+                    name: "symfony/console".to_string(),
+                    funding: vec![
+                        FundingLink {
+                            funding_type: None,
+                            url: "https://github.com/sponsors/fabpot".to_string(),
+                        },
+                        FundingLink {
+                            funding_type: None,
+                            url: "https://symfony.com/sponsor".to_string(),
+                        },
+                    ],
+                },
+                PackageFunding {
+                    name: "symfony/process".to_string(),
+                    funding: vec![FundingLink {
+                        funding_type: None,
+                        url: "https://symfony.com/sponsor".to_string(),
+                    }],
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_fund_output_empty() -> Result<(), Error> {
+        assert_eq!(parse_fund_output("[]")?, vec![]);
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! A combined report on the health of the dependencies of a project
+
+use crate::{
+    audit, outdated, ComposerAuditData, ComposerAuditOptions, ComposerOutdatedData,
+    ComposerOutdatedOptions, Error, Severity, UpdateRequirement,
+};
+
+/// The outdated packages, security advisories and abandoned packages of a
+/// project together with a score summarizing them
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct HealthReport {
+    /// The result of composer outdated
+    pub outdated: ComposerOutdatedData,
+    /// The result of composer audit
+    pub advisories: ComposerAuditData,
+    /// The names of the abandoned packages, sorted by name
+    pub abandoned: Vec<String>,
+    /// The score summarizing the report
+    pub score: HealthScore,
+}
+
+/// A rough score for the health of the dependencies from 0 (worst) to 100
+/// (all packages up to date, no advisories and nothing abandoned)
+///
+/// Starting from 100 the score is reduced by
+///
+/// * 2 for every semver-safe update
+/// * 10 for every update which is not semver-compatible
+/// * 5 for every abandoned package
+/// * 40, 25, 10 or 5 for every critical, high, medium or low severity
+///   advisory and 10 for advisories of unknown severity
+///
+/// and never goes below 0, this is a heuristic to compare projects or watch
+/// the trend of one project, not an assessment of the actual risk
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct HealthScore(pub u8);
+
+impl HealthScore {
+    /// the best possible score
+    pub const MAX: HealthScore = HealthScore(100);
+
+    /// computes the score for the given results, see [HealthScore] for the
+    /// heuristic
+    pub fn compute(outdated: &ComposerOutdatedData, advisories: &ComposerAuditData) -> Self {
+        let mut penalty: u64 = 0;
+        for package in &outdated.locked {
+            penalty += match package.latest_status {
+                UpdateRequirement::UpToDate => 0,
+                UpdateRequirement::SemverSafeUpdate => 2,
+                UpdateRequirement::UpdatePossible => 10,
+            };
+            if package.is_abandoned() {
+                penalty += 5;
+            }
+        }
+        for advisory in &advisories.advisories {
+            penalty += match advisory.severity {
+                Severity::Critical => 40,
+                Severity::High => 25,
+                Severity::Medium => 10,
+                Severity::Low => 5,
+                Severity::Unknown => 10,
+            };
+        }
+        HealthScore(100u64.saturating_sub(penalty) as u8)
+    }
+}
+
+impl std::fmt::Display for HealthScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/100", self.0)
+    }
+}
+
+impl HealthReport {
+    /// combines the results of composer outdated and composer audit
+    pub fn new(outdated: ComposerOutdatedData, advisories: ComposerAuditData) -> Self {
+        let mut abandoned: Vec<String> = outdated
+            .locked
+            .iter()
+            .filter(|package| package.is_abandoned())
+            .map(|package| package.name.to_string())
+            .collect();
+        abandoned.sort();
+        let score = HealthScore::compute(&outdated, &advisories);
+        HealthReport {
+            outdated,
+            advisories,
+            abandoned,
+            score,
+        }
+    }
+}
+
+/// the options for composer audit matching the outdated options
+fn audit_options(options: &ComposerOutdatedOptions) -> ComposerAuditOptions {
+    ComposerAuditOptions {
+        command: options.command.clone(),
+    }
+}
+
+/// runs composer outdated and composer audit and combines their results into
+/// a [HealthReport]
+pub fn health(options: &ComposerOutdatedOptions) -> Result<HealthReport, Error> {
+    let run = outdated(options)?;
+    let (_, advisories) = audit(&audit_options(options))?;
+    Ok(HealthReport::new(run.data, advisories))
+}
+
+/// async variant of [health] which runs composer outdated and composer audit
+/// concurrently, this has to be called from within a tokio runtime
+#[cfg(feature = "tokio")]
+pub async fn health_async(options: &ComposerOutdatedOptions) -> Result<HealthReport, Error> {
+    let audit_options = audit_options(options);
+    let audit = tokio::spawn(async move { crate::audit_async(&audit_options).await });
+    let run = match crate::outdated_async(options).await {
+        Ok(run) => run,
+        Err(err) => {
+            // dropping the aborted task kills composer audit instead of
+            // leaving it running in the background
+            audit.abort();
+            return Err(err);
+        }
+    };
+    let audit = audit.await.unwrap_or_else(|_| {
+        Err(std::io::Error::other("task running composer audit panicked").into())
+    });
+    let (_, advisories) = audit?;
+    Ok(HealthReport::new(run.data, advisories))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// outdated data with one package in each state, one of them abandoned
+    fn outdated_data() -> ComposerOutdatedData {
+        serde_json::from_str(
+            r#"{
+                "locked": [
+                    {
+                        "name": "swiftmailer/swiftmailer",
+                        "version": "6.3.0",
+                        "latest": "6.3.0",
+                        "latest-status": "up-to-date",
+                        "description": "",
+                        "warning": "Package swiftmailer/swiftmailer is abandoned, you should avoid using it. Use symfony/mailer instead."
+                    },
+                    {
+                        "name": "monolog/monolog",
+                        "version": "3.4.0",
+                        "latest": "3.5.0",
+                        "latest-status": "semver-safe-update",
+                        "description": ""
+                    },
+                    {
+                        "name": "doctrine/instantiator",
+                        "version": "1.4.1",
+                        "latest": "2.0.0",
+                        "latest-status": "update-possible",
+                        "description": ""
+                    }
+                ]
+            }"#,
+        )
+        .expect("outdated data should parse")
+    }
+
+    /// audit data with a single advisory of the given severity
+    fn audit_data(severity: &str) -> ComposerAuditData {
+        serde_json::from_str(&format!(
+            r#"{{
+                "advisories": {{
+                    "symfony/http-kernel": [
+                        {{
+                            "advisoryId": "PKSA-2022-0001",
+                            "packageName": "symfony/http-kernel",
+                            "affectedVersions": ">=6.0.0,<6.0.20",
+                            "title": "Some issue",
+                            "cve": null,
+                            "link": null,
+                            "severity": "{severity}"
+                        }}
+                    ]
+                }}
+            }}"#
+        ))
+        .expect("audit data should parse")
+    }
+
+    #[test]
+    fn test_health_report() {
+        let report = HealthReport::new(outdated_data(), audit_data("high"));
+        assert_eq!(report.abandoned, vec!["swiftmailer/swiftmailer"]);
+        // 5 abandoned, 2 semver-safe, 10 breaking and 25 high advisory
+        assert_eq!(report.score, HealthScore(58));
+        assert_eq!(report.score.to_string(), "58/100");
+    }
+
+    #[test]
+    fn test_health_score_bounds() {
+        let healthy = ComposerOutdatedData { locked: vec![] };
+        let no_advisories: ComposerAuditData =
+            serde_json::from_str(r#"{"advisories": []}"#).expect("audit data should parse");
+        assert_eq!(
+            HealthScore::compute(&healthy, &no_advisories),
+            HealthScore::MAX
+        );
+        let mut advisories = audit_data("critical");
+        for _ in 0..2 {
+            advisories
+                .advisories
+                .extend(audit_data("critical").advisories);
+        }
+        assert_eq!(
+            HealthScore::compute(&outdated_data(), &advisories),
+            HealthScore(0)
+        );
+    }
+}
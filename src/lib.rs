@@ -22,14 +22,87 @@
 #![warn(missing_debug_implementations)]
 #![doc = include_str!("../README.md")]
 
+mod audit;
+mod bump;
+mod cache;
+#[cfg(feature = "color")]
+mod color;
+mod config;
+#[cfg(feature = "semver")]
+mod constraint;
+mod depends;
+mod deprecation;
+mod diff;
+mod failure;
+mod format;
+mod formatter;
+mod fund;
+mod health;
+mod licenses;
+mod lock;
+mod manifest;
+mod package_name;
+mod plugins;
+mod prohibits;
+mod project;
+mod require;
+mod show;
+mod status;
+mod strict;
+mod validate;
+#[cfg(feature = "semver")]
+mod version;
+
+pub use audit::*;
+pub use bump::*;
+pub use cache::CachePolicy;
+#[cfg(feature = "color")]
+pub use color::*;
+pub use depends::*;
+pub use deprecation::*;
+pub use diff::*;
+pub use formatter::*;
+pub use fund::*;
+pub use health::*;
+pub use licenses::*;
+pub use lock::*;
+pub use manifest::*;
+pub use package_name::*;
+pub use plugins::PluginMode;
+pub use prohibits::*;
+pub use project::*;
+pub use require::*;
+pub use show::*;
+pub use status::*;
+pub use strict::*;
+pub use validate::*;
+#[cfg(feature = "semver")]
+pub use version::*;
+
+use cache::{parse_cache_policy, CacheEntry};
+use failure::classify_failure;
+use plugins::{check_allowed_plugins, parse_plugin_mode, plugin_args};
+use project::project_directory;
+
+use clap::builder::TypedValueParser as _;
 use thiserror::Error;
 
-use std::process::Command;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
 use std::str::from_utf8;
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
 /// Error type for composer_parser
+///
+/// New variants are added as more failures of composer are recognized, so
+/// matches on this type need a wildcard arm, [Error::Composer] is the
+/// fallback for failures of composer which are not described by any of the
+/// other variants
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// This means something went wrong when we were parsing the JSON output
     /// of the program
@@ -42,10 +115,159 @@ pub enum Error {
     /// This is likely to be an error when executing the program using std::process
     #[error("I/O Error: {0}")]
     StdIoError(#[from] std::io::Error),
+    /// This means composer exited with an unsuccessful exit code and did not
+    /// produce any output we could parse, failures with a recognizable reason
+    /// are reported as one of the more specific variants instead
+    #[error("composer failed with {status}: {stderr}")]
+    CommandFailed {
+        /// the exit status of the composer process
+        status: ExitStatus,
+        /// the output composer wrote to stderr
+        stderr: String,
+    },
+    /// This means composer failed because composer.lock is not up to date
+    /// with the changes in composer.json
+    #[error("composer.lock is out of date, run composer update: {stderr}")]
+    LockOutOfDate {
+        /// the exit status of the composer process
+        status: ExitStatus,
+        /// the output composer wrote to stderr
+        stderr: String,
+    },
+    /// This means composer failed because the PHP version or a PHP extension
+    /// required by a package is not available
+    #[error("platform requirements are not met: {stderr}")]
+    PlatformRequirementFailed {
+        /// the exit status of the composer process
+        status: ExitStatus,
+        /// the output composer wrote to stderr
+        stderr: String,
+    },
+    /// This means composer failed because the dependencies are not installed
+    /// in the vendor directory
+    #[error("dependencies are not installed, run composer install: {stderr}")]
+    VendorNotInstalled {
+        /// the exit status of the composer process
+        status: ExitStatus,
+        /// the output composer wrote to stderr
+        stderr: String,
+    },
+    /// This means composer reported a failure which none of the other
+    /// variants describe, the message is the one composer reported
+    #[error("composer failed: {0}")]
+    Composer(String),
+    /// This means composer did not finish within the configured timeout and
+    /// was killed
+    #[error("composer did not finish within {0:?}")]
+    Timeout(Duration),
+    /// This means composer exited successfully but did not write anything to
+    /// stdout, usually because there is no composer.lock to report on, the
+    /// reason can normally be found in the output on stderr
+    #[error("composer produced no output: {stderr}")]
+    NoOutput {
+        /// the output composer wrote to stderr
+        stderr: String,
+    },
+    /// This means writing the data as CSV failed
+    #[cfg(feature = "csv")]
+    #[error("Error writing CSV: {0}")]
+    CsvError(#[from] csv::Error),
+    /// This means the JSON output of composer does not have the expected
+    /// shape, e.g. because changed flags or a different composer version or
+    /// build produced another format, found are the top-level keys which
+    /// were present instead
+    #[error(
+        "Unexpected composer output format, expected key {expected:?} but found: {}",
+        .found.join(", ")
+    )]
+    UnexpectedFormat {
+        /// the top-level key which was expected
+        expected: String,
+        /// the top-level keys which were present
+        found: Vec<String>,
+    },
+    /// This means a string could not be parsed as an update requirement
+    #[error("Unknown update requirement: {0}")]
+    ParseUpdateRequirement(String),
+    /// This means a string could not be parsed as an indicated update
+    /// requirement
+    #[error("Unknown indicated update requirement: {0}")]
+    ParseIndicatedUpdateRequirement(String),
+    /// This means a version reported by composer could not be interpreted as
+    /// a semantic version
+    #[cfg(feature = "semver")]
+    #[error("Error parsing version as semver: {0}")]
+    SemverError(#[from] semver::Error),
+    /// This means the output of composer --version did not contain a version
+    #[cfg(feature = "semver")]
+    #[error("Could not find composer version in: {0}")]
+    ParseComposerVersion(String),
+    /// This means [strict_semver](ComposerOutdatedOptions::strict_semver)
+    /// was set and a version reported by composer, e.g. a branch like
+    /// `dev-main`, is not a semantic version
+    #[cfg(feature = "semver")]
+    #[error("Version {version} of {package} is not a semantic version")]
+    NonSemverVersion {
+        /// the name of the package
+        package: String,
+        /// the version which could not be parsed
+        version: String,
+    },
+    /// This means the installed composer is too old for the output to be
+    /// parsed by this crate
+    #[cfg(feature = "semver")]
+    #[error("composer {found} is not supported, at least {minimum} is required")]
+    UnsupportedComposerVersion {
+        /// the version of the installed composer
+        found: semver::Version,
+        /// the oldest supported version
+        minimum: semver::Version,
+    },
+    /// This means packages requested with
+    /// [only_packages](ComposerOutdatedOptions::only_packages) in strict mode
+    /// were not part of the output of composer
+    #[error("Packages not found in composer output: {}", .0.join(", "))]
+    UnknownPackages(Vec<String>),
+    /// This means a string is not a valid composer package name
+    #[error(
+        "Invalid package name {0:?}, expected vendor/name consisting of lowercase letters, digits and the separators _ . -"
+    )]
+    InvalidPackageName(String),
+    /// This means [PluginMode::Allow] was used but composer.lock contains
+    /// plugins which are not on the list
+    #[error("Plugins not allowed: {}", .0.join(", "))]
+    PluginNotAllowed(Vec<String>),
+    /// This wraps any error of a call with
+    /// [capture_raw](ComposerOutdatedOptions::capture_raw) set together with
+    /// the undecoded output of composer
+    #[error("{source}")]
+    WithRawOutput {
+        /// the error which occurred
+        source: Box<Error>,
+        /// the undecoded output of composer
+        raw: RawOutput,
+    },
+    /// This means reading or writing the cache configured with
+    /// [cache](ComposerOutdatedOptions::cache) failed, since the cache is
+    /// only an optimization [outdated] logs this error and calls composer
+    /// instead of returning it
+    #[error("I/O Error on cache file {}: {source}", .path.display())]
+    CacheIo {
+        /// the cache file or directory
+        path: PathBuf,
+        /// the underlying I/O error
+        source: std::io::Error,
+    },
 }
 
 /// These are options to modify the behaviour of the program.
-#[derive(Debug, clap::Parser)]
+///
+/// The options can also be deserialized from a configuration file, the keys
+/// are the names of the fields in kebab-case (the long flag works as an
+/// alias where it differs, e.g. `ignore` for `ignored-packages`), the
+/// options of [ComposerCommandOptions] are given at the same level
+#[derive(Debug, Clone, Default, clap::Parser, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
 pub struct ComposerOutdatedOptions {
     /// Dependencies that should be ignored
     #[clap(
@@ -53,143 +275,4357 @@ pub struct ComposerOutdatedOptions {
         long = "ignore",
         value_name = "PACKAGE_NAME",
         number_of_values = 1,
-        help = "Dependencies that should be ignored"
+        help = "Dependencies that should be ignored, may contain * wildcards like symfony/*"
+    )]
+    #[serde(alias = "ignore")]
+    pub ignored_packages: Vec<IgnorePattern>,
+    /// Only check the dependencies declared directly in composer.json, since
+    /// we call composer outdated with --locked these are the direct
+    /// dependencies recorded in the lock file
+    #[clap(
+        short = 'D',
+        long = "direct",
+        help = "Only check dependencies directly required by the root package"
+    )]
+    #[serde(alias = "direct")]
+    pub direct_only: bool,
+    /// Leave out the dev dependencies (require-dev) entirely, composer then
+    /// does not report them at all instead of just marking them
+    #[clap(long = "no-dev", help = "Do not check dev dependencies")]
+    pub no_dev: bool,
+    /// Ignore all platform requirements (PHP version and extensions) when
+    /// resolving the latest versions, e.g. to check a project targeting a
+    /// newer PHP version than the one installed
+    #[clap(
+        long = "ignore-platform-reqs",
+        help = "Ignore all platform requirements (php & ext- packages)"
+    )]
+    pub ignore_platform_reqs: bool,
+    /// Ignore only the given platform requirements, e.g. `ext-intl` or
+    /// `php`, composer also accepts a trailing `*` as a wildcard
+    #[clap(
+        long = "ignore-platform-req",
+        value_name = "REQUIREMENT",
+        number_of_values = 1,
+        help = "Ignore a specific platform requirement, may be repeated"
+    )]
+    pub ignored_platform_reqs: Vec<String>,
+    /// Which updates composer should consider when determining the latest
+    /// version of a package
+    #[clap(
+        short = 'a',
+        long = "all",
+        action = clap::ArgAction::SetTrue,
+        value_parser = clap::builder::BoolValueParser::new().map(OutdatedScope::from_all_flag),
+        help = "Report all available updates, not just semver-compatible ones"
+    )]
+    pub scope: OutdatedScope,
+    /// Extra arguments passed to composer after the ones managed by this
+    /// crate, arguments conflicting with the managed ones (e.g. overriding
+    /// `-f json`) can break parsing of the output
+    #[clap(
+        long = "composer-arg",
+        value_name = "ARG",
+        number_of_values = 1,
+        allow_hyphen_values = true,
+        help = "Extra argument to pass to composer, may be repeated"
+    )]
+    #[serde(alias = "composer-args")]
+    pub extra_args: Vec<String>,
+    /// Replace the flags this crate passes after `outdated` (by default
+    /// `-f json --no-plugins --strict --locked -m` depending on
+    /// [plugins](ComposerCommandOptions::plugins) and
+    /// [scope](ComposerOutdatedOptions::scope)) with exactly the given ones,
+    /// the output still has to be the JSON format this crate parses,
+    /// [direct_only](ComposerOutdatedOptions::direct_only), the ignored
+    /// packages and the [extra_args](ComposerOutdatedOptions::extra_args)
+    /// are still added after them
+    #[clap(
+        long = "managed-flag",
+        value_name = "FLAG",
+        number_of_values = 1,
+        allow_hyphen_values = true,
+        help = "Replace the default composer flags (-f json --strict --locked ...), may be repeated"
+    )]
+    pub managed_flags: Option<Vec<String>>,
+    /// Leave the platform packages like `php` or `ext-json` out of the
+    /// results, composer includes them if they are required, see
+    /// [PackageName::is_platform] for the exact set
+    #[clap(
+        long = "exclude-platform",
+        help = "Leave out platform packages (php, ext-*, lib-*, composer-*)"
+    )]
+    pub exclude_platform: bool,
+    /// Only report the given packages, composer has no option for this so
+    /// the parsed output is filtered, names which are not part of the output
+    /// silently produce no rows unless
+    /// [only_packages_strict](ComposerOutdatedOptions::only_packages_strict)
+    /// is set
+    #[clap(
+        short = 'o',
+        long = "only",
+        value_name = "PACKAGE_NAME",
+        number_of_values = 1,
+        help = "Only report the given dependencies"
+    )]
+    #[serde(alias = "only")]
+    pub only_packages: Vec<PackageName>,
+    /// Return an error if any of the packages given in
+    /// [only_packages](ComposerOutdatedOptions::only_packages) are not part
+    /// of the output of composer
+    #[clap(
+        long = "only-strict",
+        help = "Fail if any of the packages given with --only are not found"
+    )]
+    #[serde(alias = "only-strict")]
+    pub only_packages_strict: bool,
+    /// Fill in the repository type and reference of the
+    /// [source](PackageStatus::source) of each package from the composer.lock
+    /// in the working directory, composer outdated only reports a URL for
+    /// it even with increased verbosity
+    #[clap(
+        long = "with-source",
+        help = "Report the source repository of each package from composer.lock"
+    )]
+    pub with_source: bool,
+    /// Fail if the output of composer contains fields this crate does not
+    /// know about instead of ignoring them, see [parse_outdated_json_strict]
+    #[clap(
+        long = "strict-parsing",
+        help = "Fail if composer reports fields which are not known to this program"
     )]
-    ignored_packages: Vec<String>,
+    pub strict_parsing: bool,
+    /// Replace invalid UTF-8 in the output of composer with U+FFFD instead
+    /// of failing with [Error::Utf8Error], note that this can silently
+    /// change package names or versions containing the invalid bytes
+    #[clap(
+        long = "utf8-lossy",
+        help = "Replace invalid UTF-8 in the output of composer instead of failing"
+    )]
+    pub utf8_lossy: bool,
+    /// Skip lines before the JSON object in the output of composer, some
+    /// setups make composer print warnings (e.g. about the root package
+    /// version) to stdout before it, the skipped lines are logged as a
+    /// warning, by default they make the parsing fail
+    #[clap(
+        long = "tolerant-parse",
+        help = "Skip warning lines composer prints to stdout before its JSON output"
+    )]
+    pub tolerant_parse: bool,
+    /// Keep the undecoded output of composer in
+    /// [raw](ComposerOutdatedRun::raw), or in [Error::WithRawOutput] if the
+    /// call fails, e.g. to find out where invalid UTF-8 came from
+    #[clap(
+        long = "capture-raw",
+        help = "Keep the undecoded output of composer for troubleshooting"
+    )]
+    pub capture_raw: bool,
+    /// Cache the results keyed by the content of composer.lock, see
+    /// [CachePolicy] for when a cached result is used
+    #[clap(
+        long = "cache",
+        value_name = "DIR[:SECONDS]",
+        value_parser = parse_cache_policy,
+        help = "Cache the results in DIR for SECONDS (default 3600) until composer.lock changes"
+    )]
+    #[serde(deserialize_with = "config::deserialize_optional_cache_policy")]
+    pub cache: Option<CachePolicy>,
+    /// Check the version of composer before calling composer outdated and
+    /// fail if it is too old, this is opt-in since it takes an extra call
+    #[cfg(feature = "semver")]
+    #[clap(
+        long = "check-composer-version",
+        help = "Fail if the installed composer is too old to be supported"
+    )]
+    pub check_composer_version: bool,
+    /// Fail if the version in use or the latest version of any package is
+    /// not a semantic version (after stripping a leading `v`), e.g. because
+    /// it is installed from a branch
+    #[cfg(feature = "semver")]
+    #[clap(
+        long = "strict-semver",
+        help = "Fail if any version reported by composer is not a semantic version"
+    )]
+    pub strict_semver: bool,
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
 }
 
-/// Outer structure for parsing composer-outdated output
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct ComposerOutdatedData {
-    /// Since we call composer oudated with --locked it returns all package
-    /// information in this field
-    pub locked: Vec<PackageStatus>,
+impl ComposerOutdatedOptions {
+    /// creates a builder to construct the options programmatically
+    ///
+    /// ```
+    /// use composer_parser::{ComposerOutdatedOptions, IgnorePattern, PackageName};
+    /// use std::time::Duration;
+    ///
+    /// let options = ComposerOutdatedOptions::builder()
+    ///     .ignore(PackageName::new("psr/log")?)
+    ///     .ignore_all([
+    ///         IgnorePattern::new("symfony/*")?,
+    ///         IgnorePattern::new("doctrine/orm")?,
+    ///     ])
+    ///     .working_directory("path/to/project")
+    ///     .timeout(Duration::from_secs(60))
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     options.ignored_packages,
+    ///     vec!["psr/log", "symfony/*", "doctrine/orm"]
+    /// );
+    /// # Ok::<(), composer_parser::Error>(())
+    /// ```
+    pub fn builder() -> ComposerOutdatedOptionsBuilder {
+        ComposerOutdatedOptionsBuilder {
+            options: ComposerOutdatedOptions::default(),
+        }
+    }
 }
 
-/// Inner, per-package structure when parsing composer-outdated output
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct PackageStatus {
-    /// Package name
-    pub name: String,
-    /// Package version in use
-    pub version: String,
-    /// Latest package version available
-    pub latest: String,
-    /// Is an update required and if so, is it semver-compatible or not
-    #[serde(rename = "latest-status")]
-    pub latest_status: UpdateRequirement,
-    /// Decsription for the package
-    pub description: String,
-    /// Further notes, e.g. if a package has been abandonded
-    pub warning: Option<String>,
+/// Builder for [ComposerOutdatedOptions]
+#[derive(Debug)]
+pub struct ComposerOutdatedOptionsBuilder {
+    /// the options built so far
+    options: ComposerOutdatedOptions,
 }
 
-/// What kind of update, if any, is required for a package
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+impl ComposerOutdatedOptionsBuilder {
+    /// ignore the given package, or the packages matching the given
+    /// [IgnorePattern]
+    pub fn ignore(mut self, package_name: impl Into<IgnorePattern>) -> Self {
+        self.options.ignored_packages.push(package_name.into());
+        self
+    }
+
+    /// ignore all the given packages or patterns
+    pub fn ignore_all<I>(mut self, package_names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<IgnorePattern>,
+    {
+        self.options
+            .ignored_packages
+            .extend(package_names.into_iter().map(Into::into));
+        self
+    }
+
+    /// ignore the package with the given name, this validates the name like
+    /// [IgnorePattern::new] so it can be given as a `&str` or `String` and
+    /// may contain `*` wildcards
+    pub fn ignore_name(self, package_name: impl Into<String>) -> Result<Self, Error> {
+        Ok(self.ignore(IgnorePattern::new(package_name)?))
+    }
+
+    /// ignore the packages with the given names, e.g. an array of `&str` or
+    /// an iterator of `String`s, returns an error for the first invalid one
+    ///
+    /// ```
+    /// use composer_parser::ComposerOutdatedOptions;
+    ///
+    /// let options = ComposerOutdatedOptions::builder()
+    ///     .ignore_many(["psr/log", "symfony/*"])?
+    ///     .ignore_name(String::from("doctrine/orm"))?
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     options.ignored_packages,
+    ///     vec!["psr/log", "symfony/*", "doctrine/orm"]
+    /// );
+    /// assert!(ComposerOutdatedOptions::builder()
+    ///     .ignore_many(["psr/log", "not a package"])
+    ///     .is_err());
+    /// # Ok::<(), composer_parser::Error>(())
+    /// ```
+    pub fn ignore_many<I>(self, package_names: I) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let package_names = package_names
+            .into_iter()
+            .map(IgnorePattern::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.ignore_all(package_names))
+    }
+
+    /// only check the dependencies directly required by the root package
+    pub fn direct_only(mut self, direct_only: bool) -> Self {
+        self.options.direct_only = direct_only;
+        self
+    }
+
+    /// leave out the dev dependencies
+    pub fn no_dev(mut self, no_dev: bool) -> Self {
+        self.options.no_dev = no_dev;
+        self
+    }
+
+    /// ignore all platform requirements
+    pub fn ignore_platform_reqs(mut self, ignore_platform_reqs: bool) -> Self {
+        self.options.ignore_platform_reqs = ignore_platform_reqs;
+        self
+    }
+
+    /// ignore the given platform requirement
+    pub fn ignore_platform_req(mut self, requirement: impl Into<String>) -> Self {
+        self.options.ignored_platform_reqs.push(requirement.into());
+        self
+    }
+
+    /// which updates composer should consider
+    pub fn scope(mut self, scope: OutdatedScope) -> Self {
+        self.options.scope = scope;
+        self
+    }
+
+    /// pass an extra argument to composer after the managed ones
+    pub fn extra_arg(mut self, arg: impl Into<String>) -> Self {
+        self.options.extra_args.push(arg.into());
+        self
+    }
+
+    /// pass extra arguments to composer after the managed ones
+    pub fn extra_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.options
+            .extra_args
+            .extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// replace the flags managed by this crate with exactly the given ones
+    pub fn managed_flags<I>(mut self, flags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.options.managed_flags = Some(flags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// leave the platform packages out of the results
+    pub fn exclude_platform(mut self, exclude_platform: bool) -> Self {
+        self.options.exclude_platform = exclude_platform;
+        self
+    }
+
+    /// only report the given package
+    pub fn only(mut self, package_name: PackageName) -> Self {
+        self.options.only_packages.push(package_name);
+        self
+    }
+
+    /// only report the given packages
+    pub fn only_all<I>(mut self, package_names: I) -> Self
+    where
+        I: IntoIterator<Item = PackageName>,
+    {
+        self.options.only_packages.extend(package_names);
+        self
+    }
+
+    /// fill in the source repository of each package from composer.lock
+    pub fn with_source(mut self, with_source: bool) -> Self {
+        self.options.with_source = with_source;
+        self
+    }
+
+    /// fail if composer reports fields which are not known
+    pub fn strict_parsing(mut self, strict_parsing: bool) -> Self {
+        self.options.strict_parsing = strict_parsing;
+        self
+    }
+
+    /// replace invalid UTF-8 in the output of composer instead of failing
+    pub fn utf8_lossy(mut self, utf8_lossy: bool) -> Self {
+        self.options.utf8_lossy = utf8_lossy;
+        self
+    }
+
+    /// skip lines composer prints to stdout before its JSON output
+    pub fn tolerant_parse(mut self, tolerant_parse: bool) -> Self {
+        self.options.tolerant_parse = tolerant_parse;
+        self
+    }
+
+    /// keep the undecoded output of composer
+    pub fn capture_raw(mut self, capture_raw: bool) -> Self {
+        self.options.capture_raw = capture_raw;
+        self
+    }
+
+    /// cache the results according to the given policy
+    pub fn cache(mut self, cache: CachePolicy) -> Self {
+        self.options.cache = Some(cache);
+        self
+    }
+
+    /// fail if any of the packages to report are not found
+    pub fn only_packages_strict(mut self, only_packages_strict: bool) -> Self {
+        self.options.only_packages_strict = only_packages_strict;
+        self
+    }
+
+    /// check the version of composer before calling composer outdated
+    #[cfg(feature = "semver")]
+    pub fn check_composer_version(mut self, check_composer_version: bool) -> Self {
+        self.options.check_composer_version = check_composer_version;
+        self
+    }
+
+    /// fail if any version reported by composer is not a semantic version
+    #[cfg(feature = "semver")]
+    pub fn strict_semver(mut self, strict_semver: bool) -> Self {
+        self.options.strict_semver = strict_semver;
+        self
+    }
+
+    /// use the given composer binary instead of composer from the PATH
+    pub fn composer_binary(mut self, composer_binary: impl Into<PathBuf>) -> Self {
+        self.options.command.composer_binary = Some(composer_binary.into());
+        self
+    }
+
+    /// run the composer binary with the given php binary
+    pub fn php_binary(mut self, php_binary: impl Into<PathBuf>) -> Self {
+        self.options.command.php_binary = Some(php_binary.into());
+        self
+    }
+
+    /// run composer in the given directory
+    pub fn working_directory(mut self, working_directory: impl Into<PathBuf>) -> Self {
+        self.options.command.working_directory = Some(working_directory.into());
+        self
+    }
+
+    /// run composer in the root of the project containing the working
+    /// directory
+    pub fn discover_project_root(mut self, discover_project_root: bool) -> Self {
+        self.options.command.discover_project_root = discover_project_root;
+        self
+    }
+
+    /// run composer through the given command, e.g. `["docker", "compose",
+    /// "exec", "app"]`
+    pub fn command_prefix<I>(mut self, command_prefix: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.options.command.command_prefix = command_prefix.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// kill composer if it runs longer than the given timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.command.timeout = Some(timeout);
+        self
+    }
+
+    /// set an environment variable for composer
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.command.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// do not pass on the environment of this process to composer
+    pub fn clear_env(mut self, clear_env: bool) -> Self {
+        self.options.command.clear_env = clear_env;
+        self
+    }
+
+    /// run the commands whose text output is parsed in the current locale
+    pub fn keep_locale(mut self, keep_locale: bool) -> Self {
+        self.options.command.keep_locale = keep_locale;
+        self
+    }
+
+    /// resolve the latest versions using an additional composer repository
+    pub fn repository_url(mut self, repository_url: impl Into<String>) -> Self {
+        self.options.command.repository_url = Some(repository_url.into());
+        self
+    }
+
+    /// run composer with the given PHP memory limit, e.g. `2G` or `-1`
+    pub fn memory_limit(mut self, memory_limit: impl Into<String>) -> Self {
+        self.options.command.memory_limit = Some(memory_limit.into());
+        self
+    }
+
+    /// inspect the globally installed packages
+    pub fn global(mut self, global: bool) -> Self {
+        self.options.command.global = global;
+        self
+    }
+
+    /// which composer plugins are enabled
+    pub fn plugins(mut self, plugins: PluginMode) -> Self {
+        self.options.command.plugins = plugins;
+        self
+    }
+
+    /// retry composer calls which failed because of a network error
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.options.command.retry = Some(retry);
+        self
+    }
+
+    /// returns the options built
+    pub fn build(self) -> ComposerOutdatedOptions {
+        self.options
+    }
+}
+
+/// Which updates composer outdated should consider
+///
+/// The shape of the output is the same for both, only the meaning of
+/// [latest](PackageStatus::latest) differs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
-pub enum UpdateRequirement {
-    /// No update is required
-    UpToDate,
-    /// An update is required but it is semver-compatible to the version in use
-    SemverSafeUpdate,
-    /// An update is required to a version that is not semver-compatible
-    UpdatePossible,
+pub enum OutdatedScope {
+    /// Only consider semver-compatible updates (composer's `-m`), latest is
+    /// the newest version compatible with the one in use so packages which
+    /// only have incompatible updates are reported as up to date
+    #[default]
+    MinorOnly,
+    /// Consider all updates, latest is the newest version available
+    All,
 }
 
-impl std::fmt::Display for UpdateRequirement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            UpdateRequirement::UpToDate => {
-                write!(f, "up-to-date")
-            }
-            UpdateRequirement::SemverSafeUpdate => {
-                write!(f, "semver-safe-update")
-            }
-            UpdateRequirement::UpdatePossible => {
-                write!(f, "update-possible")
-            }
+impl OutdatedScope {
+    /// the scope selected by the presence or absence of the --all flag
+    fn from_all_flag(all: bool) -> Self {
+        if all {
+            OutdatedScope::All
+        } else {
+            OutdatedScope::MinorOnly
         }
     }
 }
 
-/// What the exit code indicated about required updates
-#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub enum IndicatedUpdateRequirement {
-    /// No update is required
-    UpToDate,
-    /// An update is required
-    UpdateRequired,
+/// These are options shared by all composer invocations to control how and
+/// where composer is run
+///
+/// When deserialized from a configuration file the
+/// [timeout](ComposerCommandOptions::timeout) is given in seconds, the
+/// [env](ComposerCommandOptions::env) as a map and the
+/// [plugins](ComposerCommandOptions::plugins) and
+/// [retry](ComposerCommandOptions::retry) in the same form as on the command
+/// line
+#[derive(Debug, Clone, Default, clap::Args, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ComposerCommandOptions {
+    /// Path to the composer binary, defaults to `composer` looked up in the PATH
+    #[clap(
+        long = "composer-binary",
+        value_name = "PATH",
+        help = "Path to the composer binary (defaults to composer in the PATH)"
+    )]
+    pub composer_binary: Option<PathBuf>,
+    /// Path to the php binary, if this is set composer is run as
+    /// `php <composer_binary>` which is useful for composer.phar
+    #[clap(
+        long = "php-binary",
+        value_name = "PATH",
+        help = "Path to the php binary used to run the composer binary"
+    )]
+    pub php_binary: Option<PathBuf>,
+    /// Command composer (or php) is run through, e.g. `ddev` or `docker
+    /// compose exec app` to run composer inside a container, the arguments
+    /// are passed exactly as given, the working directory and environment
+    /// variables apply to the first one instead of composer then
+    #[clap(
+        long = "command-prefix",
+        value_name = "ARG",
+        number_of_values = 1,
+        allow_hyphen_values = true,
+        help = "Command to run composer through, e.g. ddev or docker, may be repeated for its arguments"
+    )]
+    pub command_prefix: Vec<String>,
+    /// Directory to run composer in, defaults to the current working directory,
+    /// relative paths are resolved against the current working directory
+    #[clap(
+        short = 'C',
+        long = "working-directory",
+        value_name = "DIRECTORY",
+        help = "Directory containing the composer.json and composer.lock to check"
+    )]
+    pub working_directory: Option<PathBuf>,
+    /// Run composer in the root of the project containing the
+    /// [working_directory](ComposerCommandOptions::working_directory) (or the
+    /// current directory) instead, see [find_project_root] for how it is
+    /// found, if there is none the directory is used as is
+    #[clap(
+        long = "find-project-root",
+        help = "Run composer in the nearest parent directory containing a composer.json"
+    )]
+    pub discover_project_root: bool,
+    /// Maximum time composer may take before it is killed, by default composer
+    /// may take as long as it needs
+    #[clap(
+        long = "timeout",
+        value_name = "SECONDS",
+        value_parser = parse_timeout,
+        help = "Maximum time in seconds composer may run before it is killed"
+    )]
+    #[serde(deserialize_with = "config::deserialize_optional_secs")]
+    pub timeout: Option<Duration>,
+    /// Environment variables to set for composer, e.g. `COMPOSER_HOME` or
+    /// `COMPOSER_AUTH`, without changing the environment of this process
+    #[clap(
+        long = "env",
+        value_name = "KEY=VALUE",
+        number_of_values = 1,
+        value_parser = parse_env_var,
+        help = "Environment variable to set for composer, may be repeated"
+    )]
+    #[serde(deserialize_with = "config::deserialize_env")]
+    pub env: Vec<(String, String)>,
+    /// URL of an additional composer repository, e.g. a Satis or Private
+    /// Packagist mirror, which is passed to composer as a repository of type
+    /// composer in the `COMPOSER_REPOSITORIES` environment variable, this
+    /// changes what the latest version of a package means, a value for the
+    /// variable set in env takes precedence
+    #[clap(
+        long = "repository-url",
+        value_name = "URL",
+        help = "URL of an additional composer repository, e.g. a private Packagist mirror"
+    )]
+    pub repository_url: Option<String>,
+    /// PHP memory limit for composer, e.g. `2G` or `-1` for unlimited, which
+    /// is passed in the `COMPOSER_MEMORY_LIMIT` environment variable, this
+    /// helps with "Allowed memory size exhausted" errors for large projects, a
+    /// value for the variable set in env takes precedence
+    #[clap(
+        long = "memory-limit",
+        value_name = "LIMIT",
+        value_parser = parse_memory_limit,
+        allow_hyphen_values = true,
+        help = "PHP memory limit for composer, e.g. 2G or -1 for unlimited"
+    )]
+    #[serde(deserialize_with = "config::deserialize_optional_memory_limit")]
+    pub memory_limit: Option<String>,
+    /// Do not pass on the environment of this process to composer, only the
+    /// variables in env are set, note that composer still needs some
+    /// variables like `HOME` or `COMPOSER_HOME` to work
+    #[clap(
+        long = "clear-env",
+        help = "Run composer with only the environment variables set with --env"
+    )]
+    pub clear_env: bool,
+    /// Run the commands whose text output is parsed (e.g. status, depends
+    /// and validate) in the locale of this process instead of with `LANG`
+    /// and `LC_ALL` set to `C`, the parsers only understand composer's
+    /// English messages so this is only useful if composer is known to
+    /// print those anyway, commands with JSON output are never affected
+    #[clap(
+        long = "keep-locale",
+        help = "Do not run composer with LANG=C and LC_ALL=C for commands whose text output is parsed"
+    )]
+    pub keep_locale: bool,
+    /// Run the command on the globally installed packages (composer's
+    /// `global` prefix), composer then runs in its home directory so
+    /// [working_directory](ComposerCommandOptions::working_directory) only
+    /// affects where composer is started and `--locked` refers to the global
+    /// composer.lock
+    #[clap(
+        short = 'g',
+        long = "global",
+        help = "Inspect the globally installed packages instead of the project"
+    )]
+    pub global: bool,
+    /// Which composer plugins are enabled, by default none are
+    #[clap(
+        long = "plugins",
+        value_name = "none|all|PACKAGE_NAME,...",
+        value_parser = parse_plugin_mode,
+        default_value = "none",
+        help = "Which composer plugins to enable, none, all or a comma separated list of allowed plugin packages"
+    )]
+    #[serde(deserialize_with = "config::deserialize_plugin_mode")]
+    pub plugins: PluginMode,
+    /// Retry composer calls which failed because of what looks like a
+    /// network error, by default failures are returned immediately
+    #[clap(
+        long = "retry",
+        value_name = "ATTEMPTS[:SECONDS]",
+        value_parser = parse_retry_policy,
+        help = "Retry composer up to ATTEMPTS times if it fails with a network error, waiting SECONDS (default 1) before the first retry and doubling the wait for every further one"
+    )]
+    #[serde(deserialize_with = "config::deserialize_optional_retry_policy")]
+    pub retry: Option<RetryPolicy>,
 }
 
-impl std::fmt::Display for IndicatedUpdateRequirement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            IndicatedUpdateRequirement::UpToDate => {
-                write!(f, "up-to-date")
-            }
-            IndicatedUpdateRequirement::UpdateRequired => {
-                write!(f, "update-required")
-            }
-        }
+/// How to retry composer calls which failed because of a network error
+///
+/// A call is considered to have failed because of a network error if
+/// composer exited unsuccessfully without writing to stdout and its stderr
+/// mentions a download or connection failure, all other failures are
+/// returned immediately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How often a failed call is repeated at most
+    pub attempts: u32,
+    /// How long to wait before the first retry, the wait doubles with every
+    /// further retry
+    pub backoff: Duration,
+}
+
+/// the parts of composer error messages which indicate a network failure
+const NETWORK_ERROR_MARKERS: [&str; 9] = [
+    "curl error",
+    "could not be downloaded",
+    "failed to open stream",
+    "could not resolve host",
+    "connection timed out",
+    "connection refused",
+    "operation timed out",
+    "network is unreachable",
+    "transportexception",
+];
+
+/// did the composer call fail because of what looks like a network error
+fn is_network_failure(output: &Output) -> bool {
+    if output.status.success() || !output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return false;
     }
+    let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+    NETWORK_ERROR_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
 }
 
-/// main entry point for the composer-oudated call
-pub fn outdated(
-    options: &ComposerOutdatedOptions,
-) -> Result<(IndicatedUpdateRequirement, ComposerOutdatedData), Error> {
-    let mut cmd = Command::new("composer");
-
-    cmd.args([
-        "outdated",
-        "-f",
-        "json",
-        "--no-plugins",
-        "--strict",
-        "--locked",
-        "-m",
-    ]);
+/// how long to wait before repeating the composer call which produced the
+/// output, None if it should not be repeated
+fn retry_delay(retry: Option<&RetryPolicy>, attempt: u32, output: &Output) -> Option<Duration> {
+    let retry = retry?;
+    if attempt >= retry.attempts || !is_network_failure(output) {
+        return None;
+    }
+    let delay = retry
+        .backoff
+        .saturating_mul(2u32.saturating_pow(attempt.min(31)));
+    warn!(
+        delay_secs = delay.as_secs_f64(),
+        attempt = attempt + 1,
+        max_attempts = retry.attempts,
+        "composer failed with what looks like a network error, retrying"
+    );
+    Some(delay)
+}
 
-    for package_name in &options.ignored_packages {
-        cmd.args(["--ignore", package_name]);
+/// parses the timeout command line argument as a number of seconds
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .map_err(|e| e.to_string())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).map_err(|e| e.to_string()))
+}
+
+/// parses the retry command line argument in the form ATTEMPTS[:SECONDS]
+fn parse_retry_policy(s: &str) -> Result<RetryPolicy, String> {
+    let (attempts, backoff) = match s.split_once(':') {
+        Some((attempts, backoff)) => (attempts, parse_timeout(backoff)?),
+        None => (s, Duration::from_secs(1)),
+    };
+    Ok(RetryPolicy {
+        attempts: attempts.parse().map_err(|e| format!("{}", e))?,
+        backoff,
+    })
+}
+
+/// parses an environment variable command line argument in the form
+/// KEY=VALUE
+fn parse_env_var(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE but got {}", s))?;
+    if key.is_empty() {
+        return Err(format!("environment variable name is empty in {}", s));
     }
+    Ok((key.to_string(), value.to_string()))
+}
 
-    let output = cmd.output()?;
+/// parses a PHP memory limit command line argument, a number of bytes
+/// optionally followed by one of the suffixes K, M or G, or -1 for unlimited
+fn parse_memory_limit(s: &str) -> Result<String, String> {
+    let digits = s.strip_suffix(['k', 'K', 'm', 'M', 'g', 'G']).unwrap_or(s);
+    if s == "-1" || (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())) {
+        Ok(s.to_string())
+    } else {
+        Err(format!(
+            "expected a memory limit like 512M, 2G or -1 but got {}",
+            s
+        ))
+    }
+}
 
-    if !output.status.success() {
-        warn!(
-            "composer outdated did not return with a successful exit code: {}",
-            output.status
+/// creates the command to call composer, either directly or through the php
+/// binary if one was specified, after the command prefix if there is one, in
+/// the working directory from the options
+fn composer_command(options: &ComposerCommandOptions) -> Result<Command, Error> {
+    let composer_binary = options
+        .composer_binary
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("composer"));
+    let mut program: Vec<std::ffi::OsString> =
+        options.command_prefix.iter().map(Into::into).collect();
+    program.extend(
+        options
+            .php_binary
+            .iter()
+            .map(|php| php.clone().into_os_string()),
+    );
+    program.push(composer_binary.into_os_string());
+    let mut cmd = Command::new(&program[0]);
+    cmd.args(&program[1..]);
+    if options.working_directory.is_some() || options.discover_project_root {
+        let working_directory = std::path::absolute(project_directory(options))?;
+        if !working_directory.is_dir() {
+            return Err(Error::StdIoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "working directory {} does not exist or is not a directory",
+                    working_directory.display()
+                ),
+            )));
+        }
+        cmd.current_dir(working_directory);
+    }
+    if options.clear_env {
+        cmd.env_clear();
+    }
+    if let Some(repository_url) = &options.repository_url {
+        cmd.env(
+            "COMPOSER_REPOSITORIES",
+            serde_json::json!([{ "type": "composer", "url": repository_url }]).to_string(),
         );
-        debug!("stdout:\n{}", from_utf8(&output.stdout)?);
-        if !output.stderr.is_empty() {
-            warn!("stderr:\n{}", from_utf8(&output.stderr)?);
+    }
+    if let Some(memory_limit) = &options.memory_limit {
+        cmd.env("COMPOSER_MEMORY_LIMIT", memory_limit);
+    }
+    cmd.envs(options.env.iter().map(|(key, value)| (key, value)));
+    if options.global {
+        cmd.arg("global");
+    }
+    check_allowed_plugins(options)?;
+    Ok(cmd)
+}
+
+/// the locale commands whose text output is parsed are run in
+const TEXT_LOCALE: &str = "C";
+
+/// creates the command to call composer like [composer_command] for a
+/// command whose text output is parsed, composer translates some of its
+/// messages (and the tools it runs like git even more) so `LANG` and
+/// `LC_ALL` are set to `C` to get the English output the parsers expect,
+/// unless [keep_locale](ComposerCommandOptions::keep_locale) is set or the
+/// variables are set explicitly in [env](ComposerCommandOptions::env)
+fn text_composer_command(options: &ComposerCommandOptions) -> Result<Command, Error> {
+    let mut cmd = composer_command(options)?;
+    if !options.keep_locale {
+        for key in ["LANG", "LC_ALL"] {
+            if !options.env.iter().any(|(name, _)| name == key) {
+                cmd.env(key, TEXT_LOCALE);
+            }
         }
     }
+    Ok(cmd)
+}
 
-    let update_requirement = if output.status.success() {
-        IndicatedUpdateRequirement::UpToDate
-    } else {
-        IndicatedUpdateRequirement::UpdateRequired
+/// runs the composer command and captures its output, killing it if it
+/// exceeds the timeout from the options and retrying network failures
+/// according to the retry policy from the options
+fn run_composer(mut cmd: Command, options: &ComposerCommandOptions) -> Result<Output, Error> {
+    let mut attempt = 0;
+    loop {
+        let output = run_composer_once(&mut cmd, options)?;
+        let Some(delay) = retry_delay(options.retry.as_ref(), attempt, &output) else {
+            return Ok(output);
+        };
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// runs the composer command once and captures its output, killing it if it
+/// exceeds the timeout from the options
+fn run_composer_once(cmd: &mut Command, options: &ComposerCommandOptions) -> Result<Output, Error> {
+    debug!(command_line = ?command_line(cmd), "running composer");
+
+    let Some(timeout) = options.timeout else {
+        return Ok(cmd.output()?);
     };
 
-    let json_str = from_utf8(&output.stdout)?;
-    let data: ComposerOutdatedData = serde_json::from_str(json_str)?;
-    Ok((update_requirement, data))
-}
+    let started = Instant::now();
+    let mut child = spawn_piped(cmd)?;
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    //use pretty_assertions::{assert_eq, assert_ne};
+    // read both pipes in the background so composer can not block on a full
+    // pipe buffer while we wait for it
+    let stdout_reader = spawn_pipe_reader(child.stdout.take());
+    let stderr_reader = spawn_pipe_reader(child.stderr.take());
+
+    let status = wait_with_timeout(&mut child, Some(timeout), started)?;
+
+    Ok(Output {
+        status,
+        stdout: join_pipe_reader(stdout_reader)?,
+        stderr: join_pipe_reader(stderr_reader)?,
+    })
+}
+
+/// runs the composer command like [run_composer] but calls progress with
+/// every line composer writes to stderr as soon as it arrives, including
+/// the lines of attempts which are retried
+fn run_composer_with_progress(
+    mut cmd: Command,
+    options: &ComposerCommandOptions,
+    progress: &mut dyn FnMut(&str),
+) -> Result<Output, Error> {
+    let mut attempt = 0;
+    loop {
+        let output = run_composer_with_progress_once(&mut cmd, options, progress)?;
+        let Some(delay) = retry_delay(options.retry.as_ref(), attempt, &output) else {
+            return Ok(output);
+        };
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// runs the composer command once like [run_composer_once] but calls
+/// progress with every line composer writes to stderr as soon as it arrives
+fn run_composer_with_progress_once(
+    cmd: &mut Command,
+    options: &ComposerCommandOptions,
+    progress: &mut dyn FnMut(&str),
+) -> Result<Output, Error> {
+    debug!(command_line = ?command_line(cmd), "running composer");
+
+    let started = Instant::now();
+    let mut child = spawn_piped(cmd)?;
+    let deadline = options.timeout.map(|timeout| started + timeout);
+
+    let stdout_reader = spawn_pipe_reader(child.stdout.take());
+    let (sender, receiver) = std::sync::mpsc::channel::<Vec<u8>>();
+    let stderr_pipe = child.stderr.take();
+    std::thread::spawn(move || {
+        let Some(stderr_pipe) = stderr_pipe else {
+            return;
+        };
+        let mut reader = std::io::BufReader::new(stderr_pipe);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    // the progress callback is called on this thread since it need not be
+    // Send, the channel is disconnected once composer closes stderr
+    let mut stderr = Vec::new();
+    loop {
+        let received = match deadline {
+            Some(deadline) => {
+                receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => receiver
+                .recv()
+                .map_err(|_| std::sync::mpsc::RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(line) => {
+                progress(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+                stderr.extend(line);
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                // let wait_with_timeout do the killing and reaping
+                break;
+            }
+        }
+    }
+
+    let status = wait_with_timeout(&mut child, options.timeout, started)?;
+
+    Ok(Output {
+        status,
+        stdout: join_pipe_reader(stdout_reader)?,
+        stderr,
+    })
+}
+
+/// spawns the command with stdout and stderr piped and stdin closed
+fn spawn_piped(cmd: &mut Command) -> Result<Child, Error> {
+    Ok(cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?)
+}
+
+/// waits for the child to exit, if it runs longer than the timeout after it
+/// was started it is killed and reaped
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    started: Instant,
+) -> Result<ExitStatus, Error> {
+    let Some(timeout) = timeout else {
+        return Ok(child.wait()?);
+    };
+    let deadline = started + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            warn!(
+                timeout_secs = timeout.as_secs_f64(),
+                "composer did not finish within the timeout, killing it"
+            );
+            child.kill()?;
+            // reap the killed process so it does not linger as a zombie
+            child.wait()?;
+            return Err(Error::Timeout(timeout));
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// async variant of [run_composer] using tokio, on timeout the process is
+/// killed when the future is dropped and reaped by the tokio runtime
+#[cfg(feature = "tokio")]
+async fn run_composer_async(
+    cmd: Command,
+    options: &ComposerCommandOptions,
+) -> Result<Output, Error> {
+    let mut cmd = tokio::process::Command::from(cmd);
+    cmd.kill_on_drop(true);
+
+    let mut attempt = 0;
+    loop {
+        let output = run_composer_async_once(&mut cmd, options).await?;
+        let Some(delay) = retry_delay(options.retry.as_ref(), attempt, &output) else {
+            return Ok(output);
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// runs the tokio composer command once, see [run_composer_async]
+#[cfg(feature = "tokio")]
+async fn run_composer_async_once(
+    cmd: &mut tokio::process::Command,
+    options: &ComposerCommandOptions,
+) -> Result<Output, Error> {
+    let Some(timeout) = options.timeout else {
+        return Ok(cmd.output().await?);
+    };
+
+    match tokio::time::timeout(timeout, cmd.output()).await {
+        Ok(output) => Ok(output?),
+        Err(_) => {
+            warn!(
+                timeout_secs = timeout.as_secs_f64(),
+                "composer did not finish within the timeout, killing it"
+            );
+            Err(Error::Timeout(timeout))
+        }
+    }
+}
+
+/// reads everything from a child process pipe on a separate thread
+fn spawn_pipe_reader<R>(pipe: Option<R>) -> std::thread::JoinHandle<std::io::Result<Vec<u8>>>
+where
+    R: Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buf)?;
+        }
+        Ok(buf)
+    })
+}
+
+/// waits for a thread started by spawn_pipe_reader and returns what it read
+fn join_pipe_reader(
+    handle: std::thread::JoinHandle<std::io::Result<Vec<u8>>>,
+) -> Result<Vec<u8>, Error> {
+    handle
+        .join()
+        .map_err(|_| std::io::Error::other("thread reading composer output panicked"))?
+        .map_err(Error::from)
+}
+
+/// logs a composer call which did not return with a successful exit code,
+/// the exit code and the output are recorded as fields so log processors can
+/// filter on them
+fn log_failed_call(command: &str, output: &Output) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    warn!(
+        command,
+        exit_code = output.status.code(),
+        stderr = %stderr.trim_end(),
+        "composer did not return with a successful exit code"
+    );
+    debug!(
+        command,
+        stdout_len = output.stdout.len(),
+        stdout = %String::from_utf8_lossy(&output.stdout),
+        "stdout of the failed composer call"
+    );
+}
+
+/// creates the error returned when composer failed without producing output
+/// we could use, failures with a reason we recognize on stderr get their own
+/// variant
+fn command_failed(output: &Output) -> Error {
+    classify_failure(
+        output.status,
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+/// parses the JSON on stdout of a composer call which uses its exit code to
+/// signal something other than failure, if the call was unsuccessful and the
+/// output can not be parsed the call is treated as failed, if the call was
+/// successful but produced no output at all [Error::NoOutput] is returned
+fn parse_json_output<T>(output: &Output) -> Result<T, Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    parse_json_output_with(output, false, |json| Ok(serde_json::from_str(json)?))
+}
+
+/// decodes the output of composer as UTF-8, if lossy is set invalid bytes
+/// are replaced with U+FFFD instead of returning an error
+fn decode_output(bytes: &[u8], lossy: bool) -> Result<std::borrow::Cow<'_, str>, Error> {
+    if lossy {
+        Ok(String::from_utf8_lossy(bytes))
+    } else {
+        Ok(std::borrow::Cow::Borrowed(from_utf8(bytes)?))
+    }
+}
+
+/// like [parse_json_output] but with a custom function to parse the JSON and
+/// optionally lossy decoding of stdout, see [decode_output]
+fn parse_json_output_with<T, F>(output: &Output, lossy: bool, parse: F) -> Result<T, Error>
+where
+    F: FnOnce(&str) -> Result<T, Error>,
+{
+    let json_str = decode_output(&output.stdout, lossy)?;
+    let json_str = json_str.as_ref();
+    if output.status.success() && json_str.trim().is_empty() {
+        return Err(Error::NoOutput {
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    match parse(json_str) {
+        Ok(data) => Ok(data),
+        Err(err) if !output.status.success() => {
+            debug!(error = %err, "could not parse output of failed composer call");
+            Err(command_failed(output))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// parses the JSON output of composer outdated without running composer, e.g.
+/// for output captured elsewhere
+///
+/// ```
+/// let data = composer_parser::parse_outdated_json(r#"{"locked": []}"#)?;
+/// assert!(data.locked.is_empty());
+/// # Ok::<(), composer_parser::Error>(())
+/// ```
+pub fn parse_outdated_json(json: &str) -> Result<ComposerOutdatedData, Error> {
+    parse_outdated_bytes(json.as_bytes())
+}
+
+/// variant of [parse_outdated_json] for output which is not decoded yet,
+/// this saves the separate UTF-8 validation of the whole output since
+/// serde_json validates the strings it reads itself, invalid UTF-8 is
+/// reported as [Error::SerdeJsonError] instead of [Error::Utf8Error] then
+///
+/// ```
+/// let data = composer_parser::parse_outdated_bytes(br#"{"locked": []}"#)?;
+/// assert!(data.locked.is_empty());
+/// # Ok::<(), composer_parser::Error>(())
+/// ```
+pub fn parse_outdated_bytes(json: &[u8]) -> Result<ComposerOutdatedData, Error> {
+    serde_json::from_slice(json).map_err(|err| unexpected_format(json, "locked", err))
+}
+
+/// turns a parse error into [Error::UnexpectedFormat] if the JSON is an
+/// object without the expected top-level key, a missing key is worth a more
+/// descriptive error since it means composer produced an entirely different
+/// format, e.g. `installed` instead of `locked` without `--locked`
+pub(crate) fn unexpected_format(json: &[u8], expected: &str, err: serde_json::Error) -> Error {
+    match serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(json) {
+        Ok(object) if !object.contains_key(expected) => Error::UnexpectedFormat {
+            expected: expected.to_string(),
+            found: object.keys().cloned().collect(),
+        },
+        _ => err.into(),
+    }
+}
+
+/// Outer structure for parsing composer-outdated output
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ComposerOutdatedData {
+    /// Since we call composer oudated with --locked it returns all package
+    /// information in this field
+    pub locked: Vec<PackageStatus>,
+}
+
+impl ComposerOutdatedData {
+    /// all packages reported by composer
+    pub fn packages(&self) -> &[PackageStatus] {
+        &self.locked
+    }
+
+    /// all packages which are up to date
+    pub fn up_to_date(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.with_status(UpdateRequirement::UpToDate)
+    }
+
+    /// all packages which have an update available, semver-compatible or not
+    pub fn needing_update(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.locked
+            .iter()
+            .filter(|p| p.latest_status != UpdateRequirement::UpToDate)
+    }
+
+    /// all packages which have a semver-compatible update available
+    pub fn semver_safe(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.with_status(UpdateRequirement::SemverSafeUpdate)
+    }
+
+    /// all packages which have an update available that is not
+    /// semver-compatible to the version in use
+    pub fn breaking(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.with_status(UpdateRequirement::UpdatePossible)
+    }
+
+    /// the packages grouped by the vendor part of their name, e.g. all
+    /// `symfony/*` packages, names without a vendor like the platform
+    /// package `php` are grouped under the empty string
+    pub fn group_by_vendor(&self) -> BTreeMap<String, Vec<&PackageStatus>> {
+        let mut groups: BTreeMap<String, Vec<&PackageStatus>> = BTreeMap::new();
+        for package in &self.locked {
+            groups
+                .entry(package.name.vendor().to_string())
+                .or_default()
+                .push(package);
+        }
+        groups
+    }
+
+    /// a copy of the data without the platform packages like `php` or
+    /// `ext-json`, see [PackageName::is_platform] for the exact set
+    pub fn without_platform(&self) -> ComposerOutdatedData {
+        ComposerOutdatedData {
+            locked: self
+                .locked
+                .iter()
+                .filter(|p| !p.name.is_platform())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// all packages composer reported a warning for, this includes but is
+    /// not limited to abandoned packages
+    pub fn packages_with_warnings(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.locked.iter().filter(|p| p.warning.is_some())
+    }
+
+    /// the number of packages composer reported a warning for
+    pub fn warning_count(&self) -> usize {
+        self.packages_with_warnings().count()
+    }
+
+    /// all packages which have been abandoned by their maintainers
+    pub fn abandoned(&self) -> impl Iterator<Item = &PackageStatus> {
+        self.locked.iter().filter(|p| p.is_abandoned())
+    }
+
+    /// counts the packages by their status
+    pub fn summary(&self) -> OutdatedSummary {
+        let mut summary = OutdatedSummary {
+            total: 0,
+            up_to_date: 0,
+            semver_safe: 0,
+            breaking: 0,
+            abandoned: 0,
+        };
+        for package in &self.locked {
+            summary.total += 1;
+            match package.latest_status {
+                UpdateRequirement::UpToDate => summary.up_to_date += 1,
+                UpdateRequirement::SemverSafeUpdate => summary.semver_safe += 1,
+                UpdateRequirement::UpdatePossible => summary.breaking += 1,
+            }
+            if package.is_abandoned() {
+                summary.abandoned += 1;
+            }
+        }
+        summary
+    }
+
+    /// the most severe update requirement of any package, [None] if there are
+    /// no packages
+    pub fn most_severe(&self) -> Option<UpdateRequirement> {
+        self.locked.iter().map(|p| &p.latest_status).max().cloned()
+    }
+
+    /// is any package not up to date
+    pub fn requires_attention(&self) -> bool {
+        self.locked
+            .iter()
+            .any(|p| p.latest_status > UpdateRequirement::UpToDate)
+    }
+
+    /// all packages whose update requirement is at least as severe as the
+    /// given one, e.g. [UpdateRequirement::SemverSafeUpdate] returns all
+    /// packages which are not up to date
+    pub fn filter_by_min_severity(&self, min: UpdateRequirement) -> Vec<&PackageStatus> {
+        self.locked
+            .iter()
+            .filter(|p| p.latest_status >= min)
+            .collect()
+    }
+
+    /// all packages sorted by their update requirement with the most severe
+    /// first and by name within the same update requirement, e.g. to list the
+    /// most urgent updates first in reports
+    pub fn sorted_by_severity(&self) -> Vec<&PackageStatus> {
+        let mut packages: Vec<&PackageStatus> = self.locked.iter().collect();
+        packages.sort_by(|a, b| {
+            b.latest_status
+                .cmp(&a.latest_status)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        packages
+    }
+
+    /// is the update requirement of any package at least as severe as the
+    /// given one, e.g. to fail a CI job on breaking updates
+    pub fn has_at_least(&self, min: UpdateRequirement) -> bool {
+        self.locked.iter().any(|p| p.latest_status >= min)
+    }
+
+    /// all packages with the given update requirement
+    fn with_status(&self, status: UpdateRequirement) -> impl Iterator<Item = &PackageStatus> {
+        self.locked
+            .iter()
+            .filter(move |p| p.latest_status == status)
+    }
+}
+
+impl<'a> IntoIterator for &'a ComposerOutdatedData {
+    type Item = &'a PackageStatus;
+    type IntoIter = std::slice::Iter<'a, PackageStatus>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.locked.iter()
+    }
+}
+
+impl IntoIterator for ComposerOutdatedData {
+    type Item = PackageStatus;
+    type IntoIter = std::vec::IntoIter<PackageStatus>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.locked.into_iter()
+    }
+}
+
+/// Number of packages in each state in the composer-outdated output
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OutdatedSummary {
+    /// Number of packages in total
+    pub total: usize,
+    /// Number of packages which are up to date
+    pub up_to_date: usize,
+    /// Number of packages with a semver-compatible update available
+    pub semver_safe: usize,
+    /// Number of packages with an update available that is not
+    /// semver-compatible
+    pub breaking: usize,
+    /// Number of packages which have been abandoned, these are also counted
+    /// in one of the other states
+    pub abandoned: usize,
+}
+
+impl std::fmt::Display for OutdatedSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} up-to-date, {} semver-safe, {} breaking, {} abandoned",
+            self.up_to_date, self.semver_safe, self.breaking, self.abandoned
+        )
+    }
+}
+
+/// Inner, per-package structure when parsing composer-outdated output
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PackageStatus {
+    /// Package name
+    pub name: PackageName,
+    /// Package version in use
+    pub version: String,
+    /// Latest package version available
+    pub latest: String,
+    /// Is an update required and if so, is it semver-compatible or not
+    #[serde(rename = "latest-status")]
+    pub latest_status: UpdateRequirement,
+    /// Decsription for the package
+    pub description: String,
+    /// Further notes, e.g. if a package has been abandonded
+    pub warning: Option<String>,
+    /// Is this a dev dependency, composer does not report this so it is only
+    /// known if we could cross-reference the package with the lock file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_dev: Option<bool>,
+    /// Where the package comes from, composer outdated only reports the URL
+    /// of the source, the type and reference are only known if the run used
+    /// [with_source](ComposerOutdatedOptions::with_source)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_package_source",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source: Option<PackageSource>,
+    /// Homepage of the package, if it declares one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// Where to get help for the package, composer outdated does not report
+    /// this so it is only known after [enrich_with_show] with show data
+    /// which has it, see [InstalledPackage::support]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub support: Option<SupportLinks>,
+}
+
+/// The repository a package is obtained from
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageSource {
+    /// The kind of repository, e.g. `git`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<String>,
+    /// Where the repository can be found
+    pub url: String,
+    /// The revision in the repository, e.g. a commit hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+/// The shapes used for the source of a package, composer outdated reports
+/// just the URL while composer.lock and our own serialization use an object
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawPackageSource {
+    /// just the URL
+    Url(String),
+    /// the full source information
+    Full(PackageSource),
+}
+
+/// deserializes the source of a package from either of its shapes
+fn deserialize_package_source<'de, D>(deserializer: D) -> Result<Option<PackageSource>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: Option<RawPackageSource> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(raw.map(|raw| match raw {
+        RawPackageSource::Url(url) => PackageSource {
+            source_type: None,
+            url,
+            reference: None,
+        },
+        RawPackageSource::Full(source) => source,
+    }))
+}
+
+impl PackageStatus {
+    /// is the package abandoned according to the warning composer emitted
+    /// for it (e.g. "Package foo/bar is abandoned, you should avoid using it.
+    /// No replacement was suggested.")
+    pub fn is_abandoned(&self) -> bool {
+        self.warning
+            .as_deref()
+            .is_some_and(|warning| warning.contains(" is abandoned"))
+    }
+
+    /// the replacement package composer suggests for an abandoned package
+    /// (e.g. "Package foo/bar is abandoned, you should avoid using it. Use
+    /// baz/qux instead.")
+    pub fn abandoned_replacement(&self) -> Option<&str> {
+        let warning = self.warning.as_deref()?;
+        let (_, rest) = warning.split_once(" is abandoned")?;
+        let (_, rest) = rest.split_once("Use ")?;
+        let (replacement, _) = rest.split_once(" instead")?;
+        Some(replacement.trim())
+    }
+
+    /// is the package installed from a branch instead of a tagged release,
+    /// composer reports these as e.g. `dev-main`, `2.x-dev` or, for the
+    /// default branch, `9999999-dev`
+    ///
+    /// the update status composer reports for these is informational only
+    /// since a branch moves independently of the releases it is compared to
+    pub fn is_dev_version(&self) -> bool {
+        is_branch_version(&self.version)
+    }
+
+    /// whether the version in use is a tagged release or a branch
+    pub fn version_kind(&self) -> VersionKind {
+        if self.is_dev_version() {
+            VersionKind::Branch
+        } else {
+            VersionKind::Release
+        }
+    }
+}
+
+/// is the version as reported by composer a branch, i.e. `dev-<branch>` or a
+/// branch alias like `2.x-dev`
+fn is_branch_version(version: &str) -> bool {
+    version.starts_with("dev-") || version.ends_with("-dev")
+}
+
+/// Whether a package is installed from a tagged release or from a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionKind {
+    /// A tagged release, e.g. `1.2.3`
+    Release,
+    /// A branch, e.g. `dev-main` or `9999999-dev`
+    Branch,
+}
+
+impl std::fmt::Display for VersionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionKind::Release => {
+                write!(f, "release")
+            }
+            VersionKind::Branch => {
+                write!(f, "branch")
+            }
+        }
+    }
+}
+
+/// What kind of update, if any, is required for a package
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdateRequirement {
+    /// No update is required
+    UpToDate,
+    /// An update is required but it is semver-compatible to the version in use
+    SemverSafeUpdate,
+    /// An update is required to a version that is not semver-compatible
+    UpdatePossible,
+}
+
+impl std::fmt::Display for UpdateRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateRequirement::UpToDate => {
+                write!(f, "up-to-date")
+            }
+            UpdateRequirement::SemverSafeUpdate => {
+                write!(f, "semver-safe-update")
+            }
+            UpdateRequirement::UpdatePossible => {
+                write!(f, "update-possible")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for UpdateRequirement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up-to-date" => Ok(UpdateRequirement::UpToDate),
+            "semver-safe-update" => Ok(UpdateRequirement::SemverSafeUpdate),
+            "update-possible" => Ok(UpdateRequirement::UpdatePossible),
+            _ => Err(Error::ParseUpdateRequirement(s.to_string())),
+        }
+    }
+}
+
+/// What the exit code indicated about required updates
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndicatedUpdateRequirement {
+    /// No update is required
+    UpToDate,
+    /// An update is required
+    UpdateRequired,
+}
+
+impl std::fmt::Display for IndicatedUpdateRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndicatedUpdateRequirement::UpToDate => {
+                write!(f, "up-to-date")
+            }
+            IndicatedUpdateRequirement::UpdateRequired => {
+                write!(f, "update-required")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for IndicatedUpdateRequirement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up-to-date" => Ok(IndicatedUpdateRequirement::UpToDate),
+            "update-required" => Ok(IndicatedUpdateRequirement::UpdateRequired),
+            _ => Err(Error::ParseIndicatedUpdateRequirement(s.to_string())),
+        }
+    }
+}
+
+/// The result of a composer outdated call
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ComposerOutdatedRun {
+    /// What the exit code indicated about required updates
+    pub requirement: IndicatedUpdateRequirement,
+    /// The parsed output of composer outdated
+    pub data: ComposerOutdatedData,
+    /// Everything composer wrote to stderr, this can contain useful
+    /// diagnostics like deprecation notices even if composer succeeded,
+    /// invalid UTF-8 in it is replaced with U+FFFD
+    pub stderr: String,
+    /// The undecoded output of composer if
+    /// [capture_raw](ComposerOutdatedOptions::capture_raw) was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<RawOutput>,
+}
+
+/// The output of a composer call exactly as composer wrote it, before any
+/// UTF-8 decoding, for troubleshooting
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RawOutput {
+    /// Everything composer wrote to stdout
+    pub stdout: Vec<u8>,
+    /// Everything composer wrote to stderr
+    pub stderr: Vec<u8>,
+}
+
+impl From<&Output> for RawOutput {
+    fn from(output: &Output) -> Self {
+        RawOutput {
+            stdout: output.stdout.clone(),
+            stderr: output.stderr.clone(),
+        }
+    }
+}
+
+/// When a program built on top of this crate should exit unsuccessfully
+/// after a composer outdated run, see [ComposerOutdatedRun::exit_code]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitPolicy {
+    /// Always exit successfully
+    Never,
+    /// Fail if composer indicated that updates are required, this mirrors
+    /// the exit code of composer outdated --strict
+    #[default]
+    UpdateRequired,
+    /// Fail only if a package has an update which is not semver-compatible
+    /// to the version in use
+    Breaking,
+}
+
+impl ComposerOutdatedRun {
+    /// the exit code for a program reporting this run, 0 if the run passes
+    /// the given policy and 1 otherwise
+    pub fn exit_code(&self, policy: ExitPolicy) -> i32 {
+        let failed = match policy {
+            ExitPolicy::Never => false,
+            ExitPolicy::UpdateRequired => {
+                self.requirement == IndicatedUpdateRequirement::UpdateRequired
+            }
+            ExitPolicy::Breaking => self.data.has_at_least(UpdateRequirement::UpdatePossible),
+        };
+        i32::from(failed)
+    }
+}
+
+/// creates the composer outdated command from the options
+fn outdated_command(options: &ComposerOutdatedOptions) -> Result<Command, Error> {
+    let mut cmd = composer_command(&options.command)?;
+    cmd.args(build_args(options));
+    Ok(cmd)
+}
+
+/// the flags this crate passes to composer outdated for the given options,
+/// these are needed to parse the output and are `-f json`, `--no-plugins`
+/// unless plugins are enabled, `--strict`, `--locked` and `-m` for
+/// [OutdatedScope::MinorOnly] (the default), or the replacement for them set in
+/// [managed_flags](ComposerOutdatedOptions::managed_flags), the options for
+/// filtering like `--direct` and any extra arguments are passed after them
+///
+/// ```
+/// use composer_parser::{outdated_managed_flags, ComposerOutdatedOptions};
+///
+/// assert_eq!(
+///     outdated_managed_flags(&ComposerOutdatedOptions::default()),
+///     vec!["-f", "json", "--no-plugins", "--strict", "--locked", "-m"]
+/// );
+/// ```
+pub fn outdated_managed_flags(options: &ComposerOutdatedOptions) -> Vec<String> {
+    if let Some(flags) = &options.managed_flags {
+        return flags.clone();
+    }
+
+    let mut flags: Vec<String> = ["-f", "json"]
+        .iter()
+        .chain(plugin_args(&options.command))
+        .chain(&["--strict", "--locked"])
+        .map(|arg| arg.to_string())
+        .collect();
+
+    if options.scope == OutdatedScope::MinorOnly {
+        flags.push("-m".to_string());
+    }
+
+    flags
+}
+
+/// the arguments passed to composer for the composer outdated call
+fn build_args(options: &ComposerOutdatedOptions) -> Vec<String> {
+    let mut args = vec!["outdated".to_string()];
+
+    args.extend(outdated_managed_flags(options));
+
+    if options.direct_only {
+        args.push("--direct".to_string());
+    }
+
+    if options.no_dev {
+        args.push("--no-dev".to_string());
+    }
+
+    if options.ignore_platform_reqs {
+        args.push("--ignore-platform-reqs".to_string());
+    }
+
+    for requirement in &options.ignored_platform_reqs {
+        args.push(format!("--ignore-platform-req={}", requirement));
+    }
+
+    for package_name in &options.ignored_packages {
+        args.push("--ignore".to_string());
+        args.push(package_name.to_string());
+    }
+
+    args.extend(options.extra_args.iter().cloned());
+
+    args
+}
+
+/// interprets the output of the composer outdated call, shared by the sync
+/// and async variants
+fn outdated_output(
+    options: &ComposerOutdatedOptions,
+    output: &Output,
+) -> Result<ComposerOutdatedRun, Error> {
+    let result = parse_outdated_output(options, output);
+    if !options.capture_raw {
+        return result;
+    }
+    let raw = RawOutput::from(output);
+    match result {
+        Ok(run) => Ok(ComposerOutdatedRun {
+            raw: Some(raw),
+            ..run
+        }),
+        Err(err) => Err(Error::WithRawOutput {
+            source: Box::new(err),
+            raw,
+        }),
+    }
+}
+
+/// parses the output of the composer outdated call
+fn parse_outdated_output(
+    options: &ComposerOutdatedOptions,
+    output: &Output,
+) -> Result<ComposerOutdatedRun, Error> {
+    if !output.status.success() {
+        log_failed_call("outdated", output);
+    }
+
+    let update_requirement = if output.status.success() {
+        IndicatedUpdateRequirement::UpToDate
+    } else {
+        IndicatedUpdateRequirement::UpdateRequired
+    };
+
+    let parse = if options.strict_parsing {
+        parse_outdated_json_strict
+    } else {
+        parse_outdated_json
+    };
+    let mut data = parse_json_output_with(output, options.utf8_lossy, |json| {
+        if options.tolerant_parse {
+            parse(strip_leading_noise(json))
+        } else {
+            parse(json)
+        }
+    })?;
+    annotate_from_working_directory(&mut data, options);
+    if options.exclude_platform {
+        data.locked.retain(|p| !p.name.is_platform());
+    }
+    filter_only_packages(&mut data, options)?;
+    #[cfg(feature = "semver")]
+    if options.strict_semver {
+        ensure_semver(&data)?;
+    }
+    // stderr is only informational so invalid UTF-8 in it must not fail an
+    // otherwise successful run
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    Ok(ComposerOutdatedRun {
+        requirement: update_requirement,
+        data,
+        stderr,
+        raw: None,
+    })
+}
+
+/// the output starting with the first line which begins with `{`, the lines
+/// before it are logged, if there is no such line the output is returned
+/// unchanged so the parse error refers to all of it
+fn strip_leading_noise(output: &str) -> &str {
+    if output.trim_start().starts_with('{') {
+        return output;
+    }
+    let mut start = 0;
+    for line in output.split_inclusive('\n') {
+        if line.trim_start().starts_with('{') {
+            warn!(
+                skipped = output[..start].trim_end(),
+                "skipping output of composer before the JSON object"
+            );
+            return &output[start..];
+        }
+        start += line.len();
+    }
+    output
+}
+
+/// restricts the data to the packages given in
+/// [only_packages](ComposerOutdatedOptions::only_packages), if any
+fn filter_only_packages(
+    data: &mut ComposerOutdatedData,
+    options: &ComposerOutdatedOptions,
+) -> Result<(), Error> {
+    if options.only_packages.is_empty() {
+        return Ok(());
+    }
+    if options.only_packages_strict {
+        let unknown: Vec<String> = options
+            .only_packages
+            .iter()
+            .filter(|name| !data.locked.iter().any(|p| &p.name == *name))
+            .map(ToString::to_string)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(Error::UnknownPackages(unknown));
+        }
+    }
+    data.locked
+        .retain(|p| options.only_packages.iter().any(|name| name == &p.name));
+    Ok(())
+}
+
+/// the path of composer.lock in the working directory of the options
+fn lock_file_path(options: &ComposerCommandOptions) -> PathBuf {
+    project_directory(options).join("composer.lock")
+}
+
+/// best-effort annotation of which packages are dev dependencies and, if
+/// requested, of their sources using the composer.lock in the working
+/// directory, if there is none or it can not be read the packages are left
+/// unannotated
+fn annotate_from_working_directory(
+    data: &mut ComposerOutdatedData,
+    options: &ComposerOutdatedOptions,
+) {
+    if options.command.global {
+        // the global composer.lock is in the composer home directory
+        debug!("not reading composer.lock for global packages");
+        return;
+    }
+    let lock_file_path = lock_file_path(&options.command);
+    match parse_lock_file(&lock_file_path) {
+        Ok(lock_file) => {
+            annotate_dev(data, &lock_file);
+            if options.with_source {
+                annotate_source(data, &lock_file);
+            }
+        }
+        Err(err) => debug!(
+            path = %lock_file_path.display(),
+            error = %err,
+            "could not read composer.lock to annotate the packages"
+        ),
+    }
+}
+
+/// the program and arguments of a command as strings
+fn command_line(cmd: &Command) -> Vec<String> {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|s| s.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// the full command line, starting with the program, that [outdated] would
+/// run for the given options, this does not run composer and is useful to
+/// reproduce a call manually
+pub fn build_command(options: &ComposerOutdatedOptions) -> Result<Vec<String>, Error> {
+    let cmd = outdated_command(options)?;
+    Ok(command_line(&cmd))
+}
+
+/// Everything about the composer call [outdated] would make, see [plan]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize)]
+pub struct PlannedInvocation {
+    /// The program that is run, composer or php or the first element of the
+    /// [command_prefix](ComposerCommandOptions::command_prefix)
+    pub program: String,
+    /// The arguments passed to the program
+    pub args: Vec<String>,
+    /// The directory the program is run in, None for the current directory
+    pub cwd: Option<PathBuf>,
+    /// The environment variables set for the program in addition to (or
+    /// with [clear_env](PlannedInvocation::clear_env) instead of) the
+    /// environment of this process
+    pub env: Vec<(String, String)>,
+    /// Is the environment of this process cleared for the program
+    pub clear_env: bool,
+}
+
+/// quotes the value for a POSIX shell, values consisting only of characters
+/// without a special meaning are left as they are, all others are put in
+/// single quotes
+fn shell_quote(value: &str) -> std::borrow::Cow<'_, str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.into()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''")).into()
+    }
+}
+
+/// The invocation as a shell command line with every element quoted so it
+/// can be pasted into a POSIX shell
+impl std::fmt::Display for PlannedInvocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(cwd) = &self.cwd {
+            write!(f, "cd {} && ", shell_quote(&cwd.to_string_lossy()))?;
+        }
+        if self.clear_env {
+            write!(f, "env -i ")?;
+        }
+        for (key, value) in &self.env {
+            write!(f, "{}={} ", key, shell_quote(value))?;
+        }
+        write!(f, "{}", shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", shell_quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// assembles the composer call [outdated] would make for the given options
+/// without running it, e.g. for a dry run mode showing users what would be
+/// executed, like [outdated] this fails if the working directory does not
+/// exist or composer.lock contains plugins which are not allowed
+pub fn plan(options: &ComposerOutdatedOptions) -> Result<PlannedInvocation, Error> {
+    let cmd = outdated_command(options)?;
+    let string = |s: &std::ffi::OsStr| s.to_string_lossy().into_owned();
+    Ok(PlannedInvocation {
+        program: string(cmd.get_program()),
+        args: cmd.get_args().map(string).collect(),
+        cwd: cmd.get_current_dir().map(Path::to_path_buf),
+        env: cmd
+            .get_envs()
+            .filter_map(|(key, value)| Some((string(key), string(value?))))
+            .collect(),
+        clear_env: options.command.clear_env,
+    })
+}
+
+/// Runs composer with the given arguments, this allows replacing the actual
+/// composer call, e.g. in tests where composer is not installed
+pub trait CommandRunner {
+    /// runs composer with the given arguments and returns its output
+    fn run(&self, args: &[String]) -> Result<Output, Error>;
+}
+
+/// The default [CommandRunner] which runs composer as a system process
+#[derive(Debug)]
+pub struct SystemRunner<'a> {
+    /// the options controlling how composer is invoked
+    options: &'a ComposerCommandOptions,
+}
+
+impl<'a> SystemRunner<'a> {
+    /// creates a runner which invokes composer according to the options
+    pub fn new(options: &'a ComposerCommandOptions) -> Self {
+        SystemRunner { options }
+    }
+}
+
+impl CommandRunner for SystemRunner<'_> {
+    fn run(&self, args: &[String]) -> Result<Output, Error> {
+        let mut cmd = composer_command(self.options)?;
+        cmd.args(args);
+        run_composer(cmd, self.options)
+    }
+}
+
+/// the result for the cached output of a previous run if caching is enabled
+/// and it is still fresh, a cache which can not be read is logged and
+/// treated like a cache without an entry
+fn load_cached(
+    options: &ComposerOutdatedOptions,
+    cache_entry: Option<&CacheEntry>,
+) -> Result<Option<ComposerOutdatedRun>, Error> {
+    let Some(cache_entry) = cache_entry else {
+        return Ok(None);
+    };
+    match cache_entry.load() {
+        Ok(Some(output)) => outdated_output(options, &output).map(Some),
+        Ok(None) => Ok(None),
+        Err(err) => {
+            warn!(error = %err, "could not read the cache");
+            Ok(None)
+        }
+    }
+}
+
+/// the result for the output of composer, the output is stored in the cache
+/// if caching is enabled and it could be parsed, the cache is only an
+/// optimization so failing to store it is logged instead of failing the run
+fn store_cached(
+    options: &ComposerOutdatedOptions,
+    cache_entry: Option<&CacheEntry>,
+    output: &Output,
+) -> Result<ComposerOutdatedRun, Error> {
+    let run = outdated_output(options, output)?;
+    if let Some(cache_entry) = cache_entry {
+        if let Err(err) = cache_entry.store(output) {
+            warn!(error = %err, "could not store the result in the cache");
+        }
+    }
+    Ok(run)
+}
+
+/// main entry point for the composer-oudated call
+pub fn outdated(options: &ComposerOutdatedOptions) -> Result<ComposerOutdatedRun, Error> {
+    outdated_with(&SystemRunner::new(&options.command), options)
+}
+
+/// variant of [outdated] which uses the given runner to call composer, the
+/// runner is responsible for how composer is invoked so the command options
+/// are only used to locate the lock file in the working directory
+pub fn outdated_with<R: CommandRunner>(
+    runner: &R,
+    options: &ComposerOutdatedOptions,
+) -> Result<ComposerOutdatedRun, Error> {
+    let cache_entry = CacheEntry::for_options(options);
+    if let Some(run) = load_cached(options, cache_entry.as_ref())? {
+        return Ok(run);
+    }
+    #[cfg(feature = "semver")]
+    if options.check_composer_version {
+        ensure_supported(composer_version_with(runner)?)?;
+    }
+    let output = runner.run(&build_args(options))?;
+    store_cached(options, cache_entry.as_ref(), &output)
+}
+
+/// variant of [outdated] which calls progress with every line composer writes
+/// to stderr as soon as it arrives, e.g. to keep a CLI responsive, the full
+/// stderr output is still returned in the result
+pub fn outdated_with_progress<F>(
+    options: &ComposerOutdatedOptions,
+    mut progress: F,
+) -> Result<ComposerOutdatedRun, Error>
+where
+    F: FnMut(&str),
+{
+    let cache_entry = CacheEntry::for_options(options);
+    if let Some(run) = load_cached(options, cache_entry.as_ref())? {
+        return Ok(run);
+    }
+    #[cfg(feature = "semver")]
+    if options.check_composer_version {
+        ensure_supported(composer_version(&options.command)?)?;
+    }
+    let cmd = outdated_command(options)?;
+    let output = run_composer_with_progress(cmd, &options.command, &mut progress)?;
+    store_cached(options, cache_entry.as_ref(), &output)
+}
+
+/// async variant of [outdated] which runs composer using tokio
+#[cfg(feature = "tokio")]
+pub async fn outdated_async(
+    options: &ComposerOutdatedOptions,
+) -> Result<ComposerOutdatedRun, Error> {
+    let cache_entry = CacheEntry::for_options(options);
+    if let Some(run) = load_cached(options, cache_entry.as_ref())? {
+        return Ok(run);
+    }
+    #[cfg(feature = "semver")]
+    if options.check_composer_version {
+        let mut cmd = composer_command(&options.command)?;
+        cmd.args(VERSION_ARGS);
+        let output = run_composer_async(cmd, &options.command).await?;
+        ensure_supported(composer_version_output(&output)?)?;
+    }
+    let cmd = outdated_command(options)?;
+    let output = run_composer_async(cmd, &options.command).await?;
+    store_cached(options, cache_entry.as_ref(), &output)
+}
+
+/// runs [outdated] and returns the status of just the given package, None if
+/// it is up to date or not part of the output of composer
+pub fn outdated_one(
+    package: &PackageName,
+    options: &ComposerOutdatedOptions,
+) -> Result<Option<PackageStatus>, Error> {
+    outdated_one_with(&SystemRunner::new(&options.command), package, options)
+}
+
+/// variant of [outdated_one] which uses the given runner to call composer
+pub fn outdated_one_with<R: CommandRunner>(
+    runner: &R,
+    package: &PackageName,
+    options: &ComposerOutdatedOptions,
+) -> Result<Option<PackageStatus>, Error> {
+    let run = outdated_with(runner, options)?;
+    Ok(run
+        .data
+        .into_iter()
+        .find(|p| &p.name == package && p.latest_status != UpdateRequirement::UpToDate))
+}
+
+/// the options for running composer outdated in the given project instead
+/// of the working directory of the options
+fn project_options(options: &ComposerOutdatedOptions, project: &Path) -> ComposerOutdatedOptions {
+    let mut options = options.clone();
+    options.command.working_directory = Some(project.to_path_buf());
+    options
+}
+
+/// runs [outdated] in each of the given project directories, e.g. for the
+/// composer.json files in the subdirectories of a monorepo, the project
+/// replaces the [working_directory](ComposerCommandOptions::working_directory)
+/// of the options, a failure in one project does not stop the others
+pub fn outdated_many(
+    projects: &[PathBuf],
+    options: &ComposerOutdatedOptions,
+) -> Vec<(PathBuf, Result<ComposerOutdatedRun, Error>)> {
+    projects
+        .iter()
+        .map(|project| {
+            let result = outdated(&project_options(options, project));
+            (project.clone(), result)
+        })
+        .collect()
+}
+
+/// async variant of [outdated_many] which runs composer in all projects
+/// concurrently, this has to be called from within a tokio runtime
+#[cfg(feature = "tokio")]
+pub async fn outdated_many_async(
+    projects: &[PathBuf],
+    options: &ComposerOutdatedOptions,
+) -> Vec<(PathBuf, Result<ComposerOutdatedRun, Error>)> {
+    let handles: Vec<_> = projects
+        .iter()
+        .map(|project| {
+            let options = project_options(options, project);
+            tokio::spawn(async move { outdated_async(&options).await })
+        })
+        .collect();
+    let mut results = Vec::with_capacity(projects.len());
+    for (project, handle) in projects.iter().zip(handles) {
+        let result = handle.await.unwrap_or_else(|_| {
+            Err(std::io::Error::other("task running composer outdated panicked").into())
+        });
+        results.push((project.clone(), result));
+    }
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// output in the format of composer outdated with one package in each state
+    const SAMPLE_OUTPUT: &str = r#"{
+        "locked": [
+            {
+                "name": "doctrine/instantiator",
+                "direct-dependency": false,
+                "homepage": "https://www.doctrine-project.org/projects/instantiator.html",
+                "source": "https://github.com/doctrine/instantiator/tree/1.4.1",
+                "version": "1.4.1",
+                "latest": "2.0.0",
+                "latest-status": "update-possible",
+                "description": "A small, lightweight utility to instantiate objects in PHP without invoking their constructors",
+                "abandoned": false
+            },
+            {
+                "name": "monolog/monolog",
+                "direct-dependency": true,
+                "homepage": "https://github.com/Seldaek/monolog",
+                "source": "https://github.com/Seldaek/monolog/tree/3.4.0",
+                "version": "3.4.0",
+                "latest": "3.5.0",
+                "latest-status": "semver-safe-update",
+                "description": "Sends your logs to files, sockets, inboxes, databases and various web services",
+                "abandoned": false
+            },
+            {
+                "name": "psr/log",
+                "direct-dependency": false,
+                "homepage": "https://github.com/php-fig/log",
+                "source": "https://github.com/php-fig/log/tree/3.0.0",
+                "version": "3.0.0",
+                "latest": "3.0.0",
+                "latest-status": "up-to-date",
+                "description": "Common interface for logging libraries",
+                "abandoned": false
+            },
+            {
+                "name": "doctrine/reflection",
+                "direct-dependency": false,
+                "homepage": "https://www.doctrine-project.org/projects/reflection.html",
+                "source": "https://github.com/doctrine/reflection/tree/1.2.3",
+                "version": "1.2.3",
+                "latest": "1.2.4",
+                "latest-status": "semver-safe-update",
+                "description": "The Doctrine Reflection project is a simple library used by the various Doctrine projects which adds some additional functionality on top of the reflection functionality that comes with PHP.",
+                "abandoned": "roave/better-reflection",
+                "warning": "Package doctrine/reflection is abandoned, you should avoid using it. Use roave/better-reflection instead."
+            },
+            {
+                "name": "swiftmailer/swiftmailer",
+                "direct-dependency": true,
+                "homepage": "https://swiftmailer.symfony.com",
+                "source": "https://github.com/swiftmailer/swiftmailer/tree/v6.3.0",
+                "version": "v6.3.0",
+                "latest": "v6.3.0",
+                "latest-status": "up-to-date",
+                "description": "Swiftmailer, free feature-rich PHP mailer",
+                "abandoned": true,
+                "warning": "Package swiftmailer/swiftmailer is abandoned, you should avoid using it. No replacement was suggested."
+            }
+        ]
+    }"#;
+
+    /// parses [SAMPLE_OUTPUT]
+    fn sample_data() -> ComposerOutdatedData {
+        parse_outdated_json(SAMPLE_OUTPUT).expect("sample output should parse")
+    }
+
+    /// parses the given package name which is known to be valid
+    fn package_name(name: &str) -> PackageName {
+        PackageName::new(name).expect("package name should be valid")
+    }
+
+    /// parses the given package names which are known to be valid
+    fn package_names<const N: usize>(names: [&str; N]) -> Vec<PackageName> {
+        names.into_iter().map(package_name).collect()
+    }
+
+    /// the names of the packages in iteration order
+    fn names<'a>(packages: impl IntoIterator<Item = &'a PackageStatus>) -> Vec<&'a str> {
+        packages.into_iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// this test requires a composer.json and composer.lock in the main crate
+    /// directory (working dir of the tests)
+    #[test]
+    fn test_run_composer_outdated() -> Result<(), Error> {
+        outdated(&ComposerOutdatedOptions {
+            ignored_packages: vec![],
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// creates a directory containing a fake composer shell script, the
+    /// caller is responsible for removing the directory again
+    #[cfg(unix)]
+    fn fake_composer(name: &str, script: &str) -> Result<PathBuf, Error> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("composer-parser-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let composer = dir.join("composer");
+        std::fs::write(&composer, format!("#!/bin/sh\n{}\n", script))?;
+        std::fs::set_permissions(&composer, std::fs::Permissions::from_mode(0o755))?;
+        Ok(dir)
+    }
+
+    /// creates a fake composer which records its arguments in a file `args`
+    /// next to it and prints the given output
+    #[cfg(unix)]
+    fn recording_composer(name: &str, output: &str) -> Result<PathBuf, Error> {
+        fake_composer(
+            name,
+            &format!(
+                "printf '%s\\n' \"$@\" > \"$(dirname \"$0\")/args\"\necho '{}'",
+                output
+            ),
+        )
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_prefix() -> Result<(), Error> {
+        let dir = recording_composer("command-prefix", r#"{"locked": []}"#)?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .command_prefix([
+                    dir.join("composer").to_string_lossy().into_owned(),
+                    "exec".to_string(),
+                    "--env=A B".to_string(),
+                    "app".to_string(),
+                ])
+                .php_binary("/usr/bin/php")
+                .composer_binary("/app/composer.phar")
+                .build(),
+        );
+        let args = std::fs::read_to_string(dir.join("args"));
+        std::fs::remove_dir_all(&dir)?;
+        result?;
+        assert_eq!(
+            args?.lines().collect::<Vec<_>>(),
+            vec![
+                "exec",
+                "--env=A B",
+                "app",
+                "/usr/bin/php",
+                "/app/composer.phar",
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_working_directory() {
+        let result = outdated(&ComposerOutdatedOptions {
+            command: ComposerCommandOptions {
+                working_directory: Some(PathBuf::from("this-directory-does-not-exist")),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert!(
+            matches!(result, Err(Error::StdIoError(ref e)) if e.kind() == std::io::ErrorKind::NotFound),
+            "expected a NotFound I/O error, got {:?}",
+            result
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout() -> Result<(), Error> {
+        let dir = fake_composer("timeout", "sleep 10")?;
+
+        let start = Instant::now();
+        let result = outdated(&ComposerOutdatedOptions {
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(
+            matches!(result, Err(Error::Timeout(_))),
+            "expected a timeout, got {:?}",
+            result
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_why_without_dependents_and_invalid_utf8() -> Result<(), Error> {
+        let dir = fake_composer(
+            "why-invalid-utf8",
+            r#"printf 'bad \377 byte\nThere is no installed package depending on "psr/log"\n' >&2; exit 1"#,
+        )?;
+        let result = why(
+            "psr/log",
+            &ComposerWhyOptions {
+                command: ComposerCommandOptions {
+                    composer_binary: Some(dir.join("composer")),
+                    ..Default::default()
+                },
+                format: Some(DependsFormat::Text),
+            },
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?, vec![]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_unreadable_manifest() -> Result<(), Error> {
+        let dir = fake_composer(
+            "validate-unreadable",
+            "echo './composer.json not found.' >&2\nexit 3",
+        )?;
+        let result = validate(&ComposerValidateOptions {
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                ..Default::default()
+            },
+        });
+        std::fs::remove_dir_all(&dir)?;
+        assert!(
+            matches!(&result, Err(Error::Composer(message)) if message == "./composer.json not found."),
+            "expected Composer error, got {:?}",
+            result
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_invalid_utf8_on_stderr() -> Result<(), Error> {
+        let dir = fake_composer(
+            "validate-invalid-utf8",
+            r#"printf 'Deprecated: bad \377 byte\n./composer.json is valid\n' >&2"#,
+        )?;
+        let result = validate(&ComposerValidateOptions {
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                ..Default::default()
+            },
+        });
+        std::fs::remove_dir_all(&dir)?;
+        let report = result?;
+        assert!(report.is_valid);
+        assert!(report.errors.is_empty());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_require_dry_run_invalid_utf8_on_stderr() -> Result<(), Error> {
+        let dir = fake_composer(
+            "require-invalid-utf8",
+            r#"printf 'Deprecated: bad \377 byte\n  - Locking psr/log (3.0.0)\n' >&2"#,
+        )?;
+        let result = require_dry_run(
+            "psr/log",
+            "^3.0",
+            &ComposerRequireOptions {
+                command: ComposerCommandOptions {
+                    composer_binary: Some(dir.join("composer")),
+                    ..Default::default()
+                },
+            },
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            result?.installs,
+            vec![PlannedPackage {
+                name: "psr/log".to_string(),
+                version: "3.0.0".to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retry_network_failure() -> Result<(), Error> {
+        let dir = fake_composer(
+            "retry",
+            &format!(
+                "count=\"$(dirname \"$0\")/count\"\n\
+                 echo x >> \"$count\"\n\
+                 if [ \"$(wc -l < \"$count\")\" -lt 3 ]; then\n\
+                 echo 'curl error 6 while downloading https://repo.packagist.org/packages.json: Could not resolve host: repo.packagist.org' >&2\n\
+                 exit 100\n\
+                 fi\n\
+                 echo '{}'",
+                SAMPLE_OUTPUT.replace('\n', " ")
+            ),
+        )?;
+
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .retry(RetryPolicy {
+                    attempts: 2,
+                    backoff: Duration::from_millis(10),
+                })
+                .build(),
+        );
+        let calls = std::fs::read_to_string(dir.join("count"))?.lines().count();
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(result?.data.locked.len(), 5);
+        assert_eq!(calls, 3);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retry_other_failure_fails_fast() -> Result<(), Error> {
+        let dir = fake_composer(
+            "retry-other",
+            "echo x >> \"$(dirname \"$0\")/count\"\n\
+             echo 'composer.lock is not present' >&2\n\
+             exit 1",
+        )?;
+
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .retry(RetryPolicy {
+                    attempts: 2,
+                    backoff: Duration::from_millis(10),
+                })
+                .build(),
+        );
+        let calls = std::fs::read_to_string(dir.join("count"))?.lines().count();
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(
+            matches!(result, Err(Error::CommandFailed { .. })),
+            "expected CommandFailed, got {:?}",
+            result
+        );
+        assert_eq!(calls, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_ignored_package_from_command_line() {
+        use clap::Parser as _;
+
+        let result =
+            ComposerOutdatedOptions::try_parse_from(["composer-outdated", "-i", "Psr/Log"]);
+        let err = result.expect_err("invalid package names should be rejected");
+        assert!(err.to_string().contains("Invalid package name"), "{}", err);
+    }
+
+    #[test]
+    fn test_retry_command_line() {
+        use clap::Parser as _;
+
+        let options = ComposerOutdatedOptions::try_parse_from(["composer-outdated"])
+            .expect("parsing without arguments should succeed");
+        assert_eq!(options.command.retry, None);
+        let options =
+            ComposerOutdatedOptions::try_parse_from(["composer-outdated", "--retry", "3:0.5"])
+                .expect("parsing the retry argument should succeed");
+        assert_eq!(
+            options.command.retry,
+            Some(RetryPolicy {
+                attempts: 3,
+                backoff: Duration::from_millis(500),
+            })
+        );
+        assert_eq!(
+            parse_retry_policy("2"),
+            Ok(RetryPolicy {
+                attempts: 2,
+                backoff: Duration::from_secs(1),
+            })
+        );
+        assert!(parse_retry_policy("many").is_err());
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_timeout_async() -> Result<(), Error> {
+        let dir = fake_composer("timeout-async", "sleep 10")?;
+
+        let start = Instant::now();
+        let result = outdated_async(&ComposerOutdatedOptions {
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(
+            matches!(result, Err(Error::Timeout(_))),
+            "expected a timeout, got {:?}",
+            result
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+
+    /// creates a project directory with a composer.lock so the output of
+    /// the fake composer can be told apart by project
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_health_async_outdated_failure_stops_audit() -> Result<(), Error> {
+        let dir = fake_composer(
+            "health-async",
+            r#"case "$1" in
+audit) sleep 0.5; touch "$(dirname "$0")/audited";;
+*) exit 2;;
+esac"#,
+        )?;
+        let result = health_async(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .build(),
+        )
+        .await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let audited = dir.join("audited").exists();
+        std::fs::remove_dir_all(&dir)?;
+        assert!(result.is_err());
+        assert!(
+            !audited,
+            "composer audit kept running after outdated failed"
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn fake_project(dir: &Path, name: &str) -> Result<PathBuf, Error> {
+        let project = dir.join(name);
+        std::fs::create_dir_all(&project)?;
+        std::fs::write(project.join("composer.lock"), r#"{"packages": []}"#)?;
+        Ok(project)
+    }
+
+    /// a fake composer which fails in the project called broken and reports
+    /// the project directory as the only package otherwise
+    #[cfg(unix)]
+    const MANY_PROJECTS_SCRIPT: &str = r#"case "$PWD" in
+*/broken) echo 'not json'; exit 1;;
+*) printf '{"locked": [{"name": "project/%s", "version": "1.0.0", "latest": "1.0.0", "latest-status": "up-to-date", "description": ""}]}' "$(basename "$PWD")";;
+esac"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_many() -> Result<(), Error> {
+        let dir = fake_composer("outdated-many", MANY_PROJECTS_SCRIPT)?;
+        let projects = vec![
+            fake_project(&dir, "first")?,
+            fake_project(&dir, "broken")?,
+            fake_project(&dir, "second")?,
+        ];
+
+        let results = outdated_many(
+            &projects,
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .build(),
+        );
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, projects[0]);
+        let first = results[0].1.as_ref().expect("first project should succeed");
+        assert_eq!(names(&first.data.locked), vec!["project/first"]);
+        assert_eq!(results[1].0, projects[1]);
+        assert!(matches!(results[1].1, Err(Error::CommandFailed { .. })));
+        let second = results[2]
+            .1
+            .as_ref()
+            .expect("second project should succeed");
+        assert_eq!(names(&second.data.locked), vec!["project/second"]);
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[tokio::test]
+    async fn test_outdated_many_async() -> Result<(), Error> {
+        let dir = fake_composer("outdated-many-async", MANY_PROJECTS_SCRIPT)?;
+        let projects = vec![fake_project(&dir, "broken")?, fake_project(&dir, "first")?];
+
+        let results = outdated_many_async(
+            &projects,
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .build(),
+        )
+        .await;
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, projects[0]);
+        assert!(matches!(results[0].1, Err(Error::CommandFailed { .. })));
+        assert_eq!(results[1].0, projects[1]);
+        let first = results[1].1.as_ref().expect("first project should succeed");
+        assert_eq!(names(&first.data.locked), vec!["project/first"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_by_update_requirement() {
+        let data = sample_data();
+        assert_eq!(
+            names(data.up_to_date()),
+            vec!["psr/log", "swiftmailer/swiftmailer"]
+        );
+        assert_eq!(
+            names(data.needing_update()),
+            vec![
+                "doctrine/instantiator",
+                "monolog/monolog",
+                "doctrine/reflection"
+            ]
+        );
+        assert_eq!(
+            names(data.semver_safe()),
+            vec!["monolog/monolog", "doctrine/reflection"]
+        );
+        assert_eq!(names(data.breaking()), vec!["doctrine/instantiator"]);
+    }
+
+    #[test]
+    fn test_abandoned() {
+        let data = sample_data();
+        assert_eq!(
+            names(data.abandoned()),
+            vec!["doctrine/reflection", "swiftmailer/swiftmailer"]
+        );
+        let replacements: Vec<Option<&str>> = data
+            .abandoned()
+            .map(PackageStatus::abandoned_replacement)
+            .collect();
+        assert_eq!(replacements, vec![Some("roave/better-reflection"), None]);
+        assert!(!data.locked[0].is_abandoned());
+        assert_eq!(data.locked[0].abandoned_replacement(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_dev_leaves_out_dev_packages() -> Result<(), Error> {
+        let dir = fake_composer(
+            "no-dev",
+            r#"case " $* " in
+*" --no-dev "*) echo '{"locked": []}';;
+*) echo '{"locked": [{"name": "phpunit/phpunit", "version": "10.5.0", "latest": "10.5.1", "latest-status": "semver-safe-update", "description": ""}]}'; exit 1;;
+esac"#,
+        )?;
+        let builder = || ComposerOutdatedOptions::builder().composer_binary(dir.join("composer"));
+        let with_dev = outdated(&builder().build());
+        let without_dev = outdated(&builder().no_dev(true).build());
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(names(&with_dev?.data.locked), vec!["phpunit/phpunit"]);
+        let without_dev = without_dev?;
+        assert_eq!(
+            without_dev.requirement,
+            IndicatedUpdateRequirement::UpToDate
+        );
+        assert!(without_dev.data.locked.is_empty());
+        assert_eq!(
+            build_args(&ComposerOutdatedOptions::builder().no_dev(true).build()),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--no-dev"
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_direct_only_with_locked() -> Result<(), Error> {
+        let dir = recording_composer("direct", r#"{"locked": []}"#)?;
+        let result = outdated(&ComposerOutdatedOptions {
+            ignored_packages: vec![package_name("psr/log").into()],
+            direct_only: true,
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let args = std::fs::read_to_string(dir.join("args"));
+        std::fs::remove_dir_all(&dir)?;
+        let run = result?;
+        assert_eq!(run.requirement, IndicatedUpdateRequirement::UpToDate);
+        assert!(run.data.locked.is_empty());
+        assert_eq!(run.stderr, "");
+        assert_eq!(
+            args?.lines().collect::<Vec<_>>(),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--direct",
+                "--ignore",
+                "psr/log"
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extra_args() -> Result<(), Error> {
+        let dir = recording_composer("extra-args", r#"{"locked": []}"#)?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .extra_arg("--no-dev")
+                .extra_arg("--profile")
+                .build(),
+        );
+        let args = std::fs::read_to_string(dir.join("args"));
+        std::fs::remove_dir_all(&dir)?;
+        result?;
+        assert_eq!(
+            args?.lines().collect::<Vec<_>>(),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--no-dev",
+                "--profile"
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stderr_on_success() -> Result<(), Error> {
+        let dir = fake_composer(
+            "stderr",
+            "echo 'Deprecation Notice: something is deprecated' >&2\necho '{\"locked\": []}'",
+        )?;
+        let result = outdated(&ComposerOutdatedOptions {
+            command: ComposerCommandOptions {
+                composer_binary: Some(dir.join("composer")),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        std::fs::remove_dir_all(&dir)?;
+        let run = result?;
+        assert_eq!(run.requirement, IndicatedUpdateRequirement::UpToDate);
+        assert_eq!(run.stderr, "Deprecation Notice: something is deprecated\n");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_invalid_utf8_stderr_on_success() -> Result<(), Error> {
+        let dir = fake_composer(
+            "stderr-invalid-utf8",
+            r#"printf 'bad \377 byte' >&2; echo '{"locked": []}'"#,
+        )?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .build(),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?.stderr, "bad \u{fffd} byte");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_extra_args_from_command_line() {
+        use clap::Parser;
+
+        let options = ComposerOutdatedOptions::parse_from([
+            "composer-outdated",
+            "--composer-arg",
+            "--no-dev",
+            "--composer-arg=--profile",
+        ]);
+        assert_eq!(options.extra_args, vec!["--no-dev", "--profile"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env() -> Result<(), Error> {
+        let dir = fake_composer(
+            "env",
+            "echo \"$COMPOSER_HOME:$HOME\" >&2\necho '{\"locked\": []}'",
+        )?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .env("COMPOSER_HOME", "/tmp/composer-home")
+                .clear_env(true)
+                .build(),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?.stderr, "/tmp/composer-home:\n");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repository_url() -> Result<(), Error> {
+        let dir = fake_composer(
+            "repository-url",
+            "echo \"$COMPOSER_REPOSITORIES\" >&2\necho '{\"locked\": []}'",
+        )?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .repository_url("https://packagist.example.com")
+                .build(),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            result?.stderr,
+            "[{\"type\":\"composer\",\"url\":\"https://packagist.example.com\"}]\n"
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_memory_limit() -> Result<(), Error> {
+        let dir = fake_composer(
+            "memory-limit",
+            "echo \"$COMPOSER_MEMORY_LIMIT\" >&2\necho '{\"locked\": []}'",
+        )?;
+        let result = outdated(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .memory_limit("-1")
+                .build(),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?.stderr, "-1\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_composer_command_locale() -> Result<(), Error> {
+        /// the locale variables set for the command
+        fn locale(options: &ComposerCommandOptions) -> Result<Vec<(String, String)>, Error> {
+            Ok(text_composer_command(options)?
+                .get_envs()
+                .filter_map(|(key, value)| {
+                    Some((key.to_str()?.to_string(), value?.to_str()?.to_string()))
+                })
+                .filter(|(key, _)| key == "LANG" || key == "LC_ALL")
+                .collect())
+        }
+        let mut options = ComposerCommandOptions::default();
+        assert_eq!(
+            locale(&options)?,
+            vec![
+                ("LANG".to_string(), "C".to_string()),
+                ("LC_ALL".to_string(), "C".to_string())
+            ]
+        );
+        options.env = vec![("LANG".to_string(), "de_DE.UTF-8".to_string())];
+        assert_eq!(
+            locale(&options)?,
+            vec![
+                ("LANG".to_string(), "de_DE.UTF-8".to_string()),
+                ("LC_ALL".to_string(), "C".to_string())
+            ]
+        );
+        options.env = vec![];
+        options.keep_locale = true;
+        assert_eq!(locale(&options)?, vec![]);
+        let json = composer_command(&ComposerCommandOptions::default())?;
+        assert_eq!(json.get_envs().count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_memory_limit() {
+        for limit in ["-1", "536870912", "512M", "2G", "128k"] {
+            assert_eq!(parse_memory_limit(limit), Ok(limit.to_string()));
+        }
+        for limit in ["", "G", "-2", "2GB", "unlimited", "1.5G"] {
+            assert!(
+                parse_memory_limit(limit).is_err(),
+                "{} should be rejected",
+                limit
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_env_var() {
+        assert_eq!(
+            parse_env_var("COMPOSER_AUTH={\"a\":\"b=c\"}"),
+            Ok(("COMPOSER_AUTH".to_string(), "{\"a\":\"b=c\"}".to_string()))
+        );
+        assert!(parse_env_var("COMPOSER_HOME").is_err());
+        assert!(parse_env_var("=value").is_err());
+    }
+
+    #[test]
+    fn test_build_command_global() -> Result<(), Error> {
+        assert_eq!(
+            build_command(
+                &ComposerOutdatedOptions::builder()
+                    .global(true)
+                    .ignore(package_name("psr/log"))
+                    .build()
+            )?,
+            vec![
+                "composer",
+                "global",
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--ignore",
+                "psr/log"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan() -> Result<(), Error> {
+        let dir = std::env::temp_dir();
+        let planned = plan(
+            &ComposerOutdatedOptions::builder()
+                .working_directory(&dir)
+                .env("COMPOSER_HOME", "/tmp/composer-home")
+                .memory_limit("2G")
+                .direct_only(true)
+                .build(),
+        )?;
+        assert_eq!(
+            planned,
+            PlannedInvocation {
+                program: "composer".to_string(),
+                args: [
+                    "outdated",
+                    "-f",
+                    "json",
+                    "--no-plugins",
+                    "--strict",
+                    "--locked",
+                    "-m",
+                    "--direct"
+                ]
+                .map(String::from)
+                .to_vec(),
+                cwd: Some(std::path::absolute(&dir)?),
+                env: vec![
+                    (
+                        "COMPOSER_HOME".to_string(),
+                        "/tmp/composer-home".to_string()
+                    ),
+                    ("COMPOSER_MEMORY_LIMIT".to_string(), "2G".to_string()),
+                ],
+                clear_env: false,
+            }
+        );
+        assert_eq!(
+            planned.to_string(),
+            format!(
+                "cd {} && COMPOSER_HOME=/tmp/composer-home COMPOSER_MEMORY_LIMIT=2G composer outdated -f json --no-plugins --strict --locked -m --direct",
+                shell_quote(&std::path::absolute(&dir)?.to_string_lossy())
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_planned_invocation_quoting() {
+        let planned = PlannedInvocation {
+            program: "/opt/my composer/composer".to_string(),
+            args: ["outdated", "--ignore", "symfony/*", "it's", ""]
+                .map(String::from)
+                .to_vec(),
+            cwd: Some(PathBuf::from("/srv/my project")),
+            env: vec![("COMPOSER_AUTH".to_string(), r#"{"a": 1}"#.to_string())],
+            clear_env: true,
+        };
+        assert_eq!(
+            planned.to_string(),
+            r#"cd '/srv/my project' && env -i COMPOSER_AUTH='{"a": 1}' '/opt/my composer/composer' outdated --ignore 'symfony/*' 'it'\''s' ''"#
+        );
+    }
+
+    #[test]
+    fn test_build_args_with_plugins() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .plugins(PluginMode::All)
+                    .build()
+            ),
+            vec!["outdated", "-f", "json", "--strict", "--locked", "-m"]
+        );
+    }
+
+    #[test]
+    fn test_build_command() -> Result<(), Error> {
+        assert_eq!(
+            build_command(&ComposerOutdatedOptions::builder().build())?,
+            vec![
+                "composer",
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m"
+            ]
+        );
+        assert_eq!(
+            build_command(
+                &ComposerOutdatedOptions::builder()
+                    .php_binary("/usr/bin/php")
+                    .composer_binary("composer.phar")
+                    .ignore(package_name("psr/log"))
+                    .build()
+            )?,
+            vec![
+                "/usr/bin/php",
+                "composer.phar",
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--ignore",
+                "psr/log"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_args_without_ignored_packages() {
+        assert_eq!(
+            build_args(&ComposerOutdatedOptions::builder().build()),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_options_match_command_line_defaults() {
+        use clap::Parser as _;
+
+        let parsed = ComposerOutdatedOptions::try_parse_from(["composer-outdated"])
+            .expect("parsing without arguments should succeed");
+        assert_eq!(
+            build_args(&ComposerOutdatedOptions::default()),
+            build_args(&parsed)
+        );
+        assert_eq!(parsed.command.timeout, None);
+    }
+
+    #[test]
+    fn test_build_args_with_one_ignored_package() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .ignore(package_name("psr/log"))
+                    .build()
+            ),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--ignore",
+                "psr/log"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_args_with_many_ignored_packages() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .ignore_all(package_names(["psr/log", "symfony/console"]))
+                    .ignore_name("symfony/polyfill-*")
+                    .expect("pattern should be valid")
+                    .build()
+            ),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--ignore",
+                "psr/log",
+                "--ignore",
+                "symfony/console",
+                "--ignore",
+                "symfony/polyfill-*"
+            ]
+        );
+    }
 
-    /// this test requires a composer.json and composer.lock in the main crate
-    /// directory (working dir of the tests)
     #[test]
-    fn test_run_composer_outdated() -> Result<(), Error> {
-        outdated(&ComposerOutdatedOptions {
-            ignored_packages: vec![],
-        })?;
+    fn test_parse_outdated_json() -> Result<(), Error> {
+        let data = parse_outdated_json(SAMPLE_OUTPUT)?;
+        assert_eq!(data.locked.len(), 5);
+        assert_eq!(data.locked[2].latest_status, UpdateRequirement::UpToDate);
+        assert_eq!(parse_outdated_json_strict(SAMPLE_OUTPUT)?.locked.len(), 5);
+        assert!(matches!(
+            parse_outdated_json(r#"{"installed": []}"#),
+            Err(Error::UnexpectedFormat { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = sample_data().summary();
+        assert_eq!(
+            summary,
+            OutdatedSummary {
+                total: 5,
+                up_to_date: 2,
+                semver_safe: 2,
+                breaking: 1,
+                abandoned: 2,
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "2 up-to-date, 2 semver-safe, 1 breaking, 2 abandoned"
+        );
+    }
+
+    #[test]
+    fn test_most_severe() {
+        let mut data = sample_data();
+        assert_eq!(data.most_severe(), Some(UpdateRequirement::UpdatePossible));
+        assert!(data.requires_attention());
+        data.locked
+            .retain(|p| p.latest_status != UpdateRequirement::UpdatePossible);
+        assert_eq!(
+            data.most_severe(),
+            Some(UpdateRequirement::SemverSafeUpdate)
+        );
+        data.locked
+            .retain(|p| p.latest_status == UpdateRequirement::UpToDate);
+        assert_eq!(data.most_severe(), Some(UpdateRequirement::UpToDate));
+        assert!(!data.requires_attention());
+    }
+
+    #[test]
+    fn test_most_severe_without_packages() {
+        let data = ComposerOutdatedData { locked: vec![] };
+        assert_eq!(data.most_severe(), None);
+        assert!(!data.requires_attention());
+    }
+
+    /// sample data with the platform packages php and ext-json added
+    fn sample_data_with_platform() -> ComposerOutdatedData {
+        let mut data = sample_data();
+        for name in ["php", "ext-json"] {
+            let mut package = data.locked[2].clone();
+            package.name = package_name(name);
+            data.locked.push(package);
+        }
+        data
+    }
+
+    #[test]
+    fn test_without_platform() {
+        let data = sample_data_with_platform();
+        assert_eq!(data.locked.len(), 7);
+        assert_eq!(
+            names(&data.without_platform().locked),
+            names(&sample_data().locked)
+        );
+        assert_eq!(data.group_by_vendor()[""].len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_exclude_platform() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: r#"{"locked": [
+                {"name": "php", "version": "8.1.2", "latest": "8.1.2", "latest-status": "up-to-date", "description": ""},
+                {"name": "psr/log", "version": "1.1.4", "latest": "3.0.0", "latest-status": "update-possible", "description": ""},
+                {"name": "ext-json", "version": "8.1.2", "latest": "8.1.2", "latest-status": "up-to-date", "description": ""}
+            ]}"#,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let run = outdated_with(&runner, &ComposerOutdatedOptions::builder().build())?;
+        assert_eq!(names(&run.data.locked), vec!["php", "psr/log", "ext-json"]);
+        let run = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .exclude_platform(true)
+                .build(),
+        )?;
+        assert_eq!(names(&run.data.locked), vec!["psr/log"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_vendor() {
+        let data = sample_data();
+        let groups = data.group_by_vendor();
+        assert_eq!(
+            groups.keys().collect::<Vec<_>>(),
+            vec!["doctrine", "monolog", "psr", "swiftmailer"]
+        );
+        assert_eq!(
+            names(groups["doctrine"].iter().copied()),
+            vec!["doctrine/instantiator", "doctrine/reflection"]
+        );
+        assert_eq!(names(groups["psr"].iter().copied()), vec!["psr/log"]);
+        assert!(ComposerOutdatedData { locked: vec![] }
+            .group_by_vendor()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_packages_with_warnings() {
+        let mut data = sample_data();
+        assert_eq!(data.warning_count(), 2);
+        data.locked[1].warning =
+            Some("Package monolog/monolog has a security advisory".to_string());
+        assert_eq!(
+            names(data.packages_with_warnings()),
+            vec![
+                "monolog/monolog",
+                "doctrine/reflection",
+                "swiftmailer/swiftmailer"
+            ]
+        );
+        assert_eq!(data.warning_count(), 3);
+        assert_eq!(
+            names(data.abandoned()),
+            vec!["doctrine/reflection", "swiftmailer/swiftmailer"]
+        );
+    }
+
+    #[test]
+    fn test_is_dev_version() {
+        let mut data = sample_data();
+        let package = &mut data.locked[0];
+        assert!(!package.is_dev_version());
+        assert_eq!(package.version_kind(), VersionKind::Release);
+        for version in ["dev-main", "9999999-dev", "2.x-dev", "dev-feature/foo"] {
+            package.version = version.to_string();
+            assert!(package.is_dev_version(), "{} should be a branch", version);
+            assert_eq!(package.version_kind(), VersionKind::Branch);
+        }
+        package.version = "1.0.0-beta1".to_string();
+        assert!(!package.is_dev_version());
+    }
+
+    #[test]
+    fn test_iterate_packages() {
+        let data = sample_data();
+        let mut iterated = vec![];
+        for package in &data {
+            iterated.push(package.name.as_str());
+        }
+        assert_eq!(iterated, names(data.packages()));
+        assert_eq!(data.packages().len(), 5);
+        let owned: Vec<PackageName> = sample_data().into_iter().map(|p| p.name).collect();
+        assert_eq!(owned, iterated);
+    }
+
+    #[test]
+    fn test_filter_by_min_severity() {
+        let data = sample_data();
+        assert_eq!(
+            names(data.filter_by_min_severity(UpdateRequirement::UpToDate)),
+            vec![
+                "doctrine/instantiator",
+                "monolog/monolog",
+                "psr/log",
+                "doctrine/reflection",
+                "swiftmailer/swiftmailer"
+            ]
+        );
+        assert_eq!(
+            names(data.filter_by_min_severity(UpdateRequirement::SemverSafeUpdate)),
+            vec![
+                "doctrine/instantiator",
+                "monolog/monolog",
+                "doctrine/reflection"
+            ]
+        );
+        assert_eq!(
+            names(data.filter_by_min_severity(UpdateRequirement::UpdatePossible)),
+            vec!["doctrine/instantiator"]
+        );
+    }
+
+    #[test]
+    fn test_parse_outdated_bytes() -> Result<(), Error> {
+        let data = parse_outdated_bytes(SAMPLE_OUTPUT.as_bytes())?;
+        assert_eq!(
+            names(&data.locked),
+            names(&parse_outdated_json(SAMPLE_OUTPUT)?.locked)
+        );
+        assert!(matches!(
+            parse_outdated_bytes(b"{\"locked\": [{\"name\": \"\xff\"}]}"),
+            Err(Error::SerdeJsonError(_))
+        ));
+        assert!(matches!(
+            parse_outdated_bytes(br#"{"installed": []}"#),
+            Err(Error::UnexpectedFormat { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_outdated_json_unexpected_format() {
+        let result = parse_outdated_json(r#"{"installed": [], "warnings": []}"#);
+        assert!(
+            matches!(&result, Err(Error::UnexpectedFormat { expected, found })
+                if expected == "locked" && found == &["installed", "warnings"]),
+            "expected UnexpectedFormat, got {:?}",
+            result
+        );
+        assert!(matches!(
+            parse_outdated_json(r#"{"locked": [{"name": "psr/log"}]}"#),
+            Err(Error::SerdeJsonError(_))
+        ));
+        assert!(matches!(
+            parse_outdated_json("[]"),
+            Err(Error::SerdeJsonError(_))
+        ));
+    }
+
+    #[test]
+    fn test_sorted_by_severity() {
+        assert_eq!(
+            names(sample_data().sorted_by_severity()),
+            vec![
+                "doctrine/instantiator",
+                "doctrine/reflection",
+                "monolog/monolog",
+                "psr/log",
+                "swiftmailer/swiftmailer"
+            ]
+        );
+        assert!(ComposerOutdatedData { locked: vec![] }
+            .sorted_by_severity()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let mut run = ComposerOutdatedRun {
+            requirement: IndicatedUpdateRequirement::UpdateRequired,
+            data: sample_data(),
+            stderr: String::new(),
+            raw: None,
+        };
+        assert_eq!(run.exit_code(ExitPolicy::Never), 0);
+        assert_eq!(run.exit_code(ExitPolicy::UpdateRequired), 1);
+        assert_eq!(run.exit_code(ExitPolicy::Breaking), 1);
+
+        run.data
+            .locked
+            .retain(|p| p.latest_status != UpdateRequirement::UpdatePossible);
+        assert_eq!(run.exit_code(ExitPolicy::UpdateRequired), 1);
+        assert_eq!(run.exit_code(ExitPolicy::Breaking), 0);
+
+        run.requirement = IndicatedUpdateRequirement::UpToDate;
+        run.data
+            .locked
+            .retain(|p| p.latest_status == UpdateRequirement::UpToDate);
+        assert_eq!(run.exit_code(ExitPolicy::Never), 0);
+        assert_eq!(run.exit_code(ExitPolicy::UpdateRequired), 0);
+        assert_eq!(run.exit_code(ExitPolicy::Breaking), 0);
+        assert_eq!(ExitPolicy::default(), ExitPolicy::UpdateRequired);
+    }
+
+    #[test]
+    fn test_has_at_least() {
+        let mut data = sample_data();
+        assert!(data.has_at_least(UpdateRequirement::UpToDate));
+        assert!(data.has_at_least(UpdateRequirement::SemverSafeUpdate));
+        assert!(data.has_at_least(UpdateRequirement::UpdatePossible));
+
+        data.locked
+            .retain(|p| p.latest_status != UpdateRequirement::UpdatePossible);
+        assert!(data.has_at_least(UpdateRequirement::SemverSafeUpdate));
+        assert!(!data.has_at_least(UpdateRequirement::UpdatePossible));
+
+        data.locked
+            .retain(|p| p.latest_status == UpdateRequirement::UpToDate);
+        assert!(data.has_at_least(UpdateRequirement::UpToDate));
+        assert!(!data.has_at_least(UpdateRequirement::SemverSafeUpdate));
+
+        data.locked.clear();
+        assert!(!data.has_at_least(UpdateRequirement::UpToDate));
+        assert!(data
+            .filter_by_min_severity(UpdateRequirement::UpToDate)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_update_requirement_display_round_trip() -> Result<(), Error> {
+        for requirement in [
+            UpdateRequirement::UpToDate,
+            UpdateRequirement::SemverSafeUpdate,
+            UpdateRequirement::UpdatePossible,
+        ] {
+            assert_eq!(
+                requirement.to_string().parse::<UpdateRequirement>()?,
+                requirement
+            );
+        }
+        assert!(matches!(
+            "semver-safe".parse::<UpdateRequirement>(),
+            Err(Error::ParseUpdateRequirement(s)) if s == "semver-safe"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_indicated_update_requirement_round_trip() -> Result<(), Error> {
+        for requirement in [
+            IndicatedUpdateRequirement::UpToDate,
+            IndicatedUpdateRequirement::UpdateRequired,
+        ] {
+            assert_eq!(
+                requirement
+                    .to_string()
+                    .parse::<IndicatedUpdateRequirement>()?,
+                requirement
+            );
+        }
+        assert!(matches!(
+            "UpdateRequired".parse::<IndicatedUpdateRequirement>(),
+            Err(Error::ParseIndicatedUpdateRequirement(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_indicated_update_requirement_json() -> Result<(), Error> {
+        assert_eq!(
+            serde_json::to_string(&IndicatedUpdateRequirement::UpToDate)?,
+            "\"up-to-date\""
+        );
+        assert_eq!(
+            serde_json::to_string(&IndicatedUpdateRequirement::UpdateRequired)?,
+            "\"update-required\""
+        );
+        assert_eq!(
+            serde_json::from_str::<IndicatedUpdateRequirement>("\"update-required\"")?,
+            IndicatedUpdateRequirement::UpdateRequired
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_args_with_managed_flags() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .managed_flags(["--format=json", "--locked"])
+                    .direct_only(true)
+                    .ignore(package_name("psr/log"))
+                    .extra_arg("--no-dev")
+                    .build()
+            ),
+            vec![
+                "outdated",
+                "--format=json",
+                "--locked",
+                "--direct",
+                "--ignore",
+                "psr/log",
+                "--no-dev"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outdated_managed_flags() {
+        assert_eq!(
+            outdated_managed_flags(
+                &ComposerOutdatedOptions::builder()
+                    .plugins(PluginMode::All)
+                    .scope(OutdatedScope::All)
+                    .build()
+            ),
+            vec!["-f", "json", "--strict", "--locked"]
+        );
+        let options = ComposerOutdatedOptions::builder()
+            .managed_flags(["--format=json"])
+            .build();
+        assert_eq!(outdated_managed_flags(&options), vec!["--format=json"]);
+        assert_eq!(build_args(&options), vec!["outdated", "--format=json"]);
+    }
+
+    #[test]
+    fn test_managed_flags_from_command_line() {
+        use clap::Parser as _;
+
+        let options = ComposerOutdatedOptions::try_parse_from([
+            "composer-outdated",
+            "--managed-flag",
+            "--format=json",
+            "--managed-flag",
+            "--no-plugins",
+        ])
+        .expect("parsing managed flags should succeed");
+        assert_eq!(
+            options.managed_flags,
+            Some(vec![
+                "--format=json".to_string(),
+                "--no-plugins".to_string()
+            ])
+        );
+        assert_eq!(
+            build_args(&options),
+            vec!["outdated", "--format=json", "--no-plugins"]
+        );
+    }
+
+    #[test]
+    fn test_build_args_ignoring_platform_reqs() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .ignore_platform_reqs(true)
+                    .ignore_platform_req("ext-intl")
+                    .ignore_platform_req("php")
+                    .build()
+            ),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked",
+                "-m",
+                "--ignore-platform-reqs",
+                "--ignore-platform-req=ext-intl",
+                "--ignore-platform-req=php"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_platform_reqs_from_command_line() {
+        use clap::Parser as _;
+
+        let options = ComposerOutdatedOptions::try_parse_from([
+            "composer-outdated",
+            "--ignore-platform-reqs",
+            "--ignore-platform-req",
+            "ext-*",
+        ])
+        .expect("parsing platform requirement options should succeed");
+        assert!(options.ignore_platform_reqs);
+        assert_eq!(options.ignored_platform_reqs, vec!["ext-*"]);
+    }
+
+    #[test]
+    fn test_build_args_all_scope() {
+        assert_eq!(
+            build_args(
+                &ComposerOutdatedOptions::builder()
+                    .scope(OutdatedScope::All)
+                    .build()
+            ),
+            vec![
+                "outdated",
+                "-f",
+                "json",
+                "--no-plugins",
+                "--strict",
+                "--locked"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_scope_from_command_line() {
+        use clap::Parser;
+
+        let options = ComposerOutdatedOptions::parse_from(["composer-outdated"]);
+        assert_eq!(options.scope, OutdatedScope::MinorOnly);
+        let options = ComposerOutdatedOptions::parse_from(["composer-outdated", "--all"]);
+        assert_eq!(options.scope, OutdatedScope::All);
+        let options = ComposerOutdatedOptions::parse_from(["composer-outdated", "-a"]);
+        assert_eq!(options.scope, OutdatedScope::All);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_progress() -> Result<(), Error> {
+        let dir = fake_composer(
+            "progress",
+            "echo 'Loading composer repositories' >&2\necho 'Reading composer.lock' >&2\necho '{\"locked\": []}'",
+        )?;
+        let mut lines = vec![];
+        let result = outdated_with_progress(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .build(),
+            |line| lines.push(line.to_string()),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        let run = result?;
+        assert_eq!(
+            lines,
+            vec!["Loading composer repositories", "Reading composer.lock"]
+        );
+        assert_eq!(
+            run.stderr,
+            "Loading composer repositories\nReading composer.lock\n"
+        );
+        assert!(run.data.locked.is_empty());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_progress_timeout() -> Result<(), Error> {
+        let dir = fake_composer("progress-timeout", "echo 'Loading' >&2\nsleep 10")?;
+        let start = Instant::now();
+        let mut lines = vec![];
+        let result = outdated_with_progress(
+            &ComposerOutdatedOptions::builder()
+                .composer_binary(dir.join("composer"))
+                .timeout(Duration::from_millis(200))
+                .build(),
+            |line| lines.push(line.to_string()),
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert!(
+            matches!(result, Err(Error::Timeout(_))),
+            "expected a timeout, got {:?}",
+            result
+        );
+        assert_eq!(lines, vec!["Loading"]);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        Ok(())
+    }
+
+    /// a runner returning canned output and recording the arguments
+    #[cfg(unix)]
+    struct MockRunner {
+        /// the exit code to report
+        exit_code: i32,
+        /// what to report on stdout
+        stdout: &'static str,
+        /// the arguments of the last call
+        args: std::cell::RefCell<Vec<String>>,
+    }
+
+    #[cfg(unix)]
+    impl CommandRunner for MockRunner {
+        fn run(&self, args: &[String]) -> Result<Output, Error> {
+            use std::os::unix::process::ExitStatusExt;
+
+            *self.args.borrow_mut() = args.to_vec();
+            Ok(Output {
+                status: ExitStatus::from_raw(self.exit_code << 8),
+                stdout: self.stdout.as_bytes().to_vec(),
+                stderr: vec![],
+            })
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_raw() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 0,
+            stdout: r#"{"locked": []}"#,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let run = outdated_with(&runner, &ComposerOutdatedOptions::builder().build())?;
+        assert_eq!(run.raw, None);
+        let run = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder().capture_raw(true).build(),
+        )?;
+        assert_eq!(
+            run.raw,
+            Some(RawOutput {
+                stdout: br#"{"locked": []}"#.to_vec(),
+                stderr: vec![],
+            })
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_raw_invalid_utf8() -> Result<(), Error> {
+        let dir = fake_composer(
+            "capture-raw",
+            r#"printf 'bad \377 byte' >&2; echo '{"locked": []}'"#,
+        )?;
+        let builder = || ComposerOutdatedOptions::builder().composer_binary(dir.join("composer"));
+        let without_raw = outdated(&builder().build());
+        let with_raw = outdated(&builder().capture_raw(true).build());
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(without_raw?.stderr, "bad \u{fffd} byte");
+        assert_eq!(
+            with_raw?.raw,
+            Some(RawOutput {
+                stdout: b"{\"locked\": []}\n".to_vec(),
+                stderr: b"bad \xff byte".to_vec(),
+            })
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_utf8_lossy() -> Result<(), Error> {
+        let dir = fake_composer(
+            "utf8-lossy",
+            r#"printf 'bad \377 byte' >&2; printf '{"locked": [{"name": "vendor/package", "version": "1.0.0", "latest": "1.0.0", "latest-status": "up-to-date", "description": "caf\351"}]}'"#,
+        )?;
+        let builder = || ComposerOutdatedOptions::builder().composer_binary(dir.join("composer"));
+        let strict = outdated(&builder().build());
+        let lossy = outdated(&builder().utf8_lossy(true).build());
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(matches!(strict, Err(Error::Utf8Error(_))));
+        let lossy = lossy?;
+        assert_eq!(lossy.stderr, "bad \u{fffd} byte");
+        assert_eq!(lossy.data.locked[0].description, "caf\u{fffd}");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_show_stream() -> Result<(), Error> {
+        /// the options to run the fake composer in the directory
+        fn options(dir: &Path) -> ComposerShowOptions {
+            ComposerShowOptions {
+                command: ComposerCommandOptions {
+                    composer_binary: Some(dir.join("composer")),
+                    ..Default::default()
+                },
+            }
+        }
+        let dir = fake_composer(
+            "show-stream",
+            r#"echo '{"locked": [{"name": "psr/log", "version": "3.0.0", "description": ""}]}'"#,
+        )?;
+        let mut names = vec![];
+        let result = show_stream(&options(&dir), |package| names.push(package.name));
+        std::fs::remove_dir_all(&dir)?;
+        result?;
+        assert_eq!(names, vec!["psr/log"]);
+
+        let dir = fake_composer("show-stream-failing", "echo '{\"locked\": []}'\nexit 1")?;
+        let result = show_stream(&options(&dir), |_| {});
+        std::fs::remove_dir_all(&dir)?;
+        assert!(matches!(result, Err(Error::CommandFailed { .. })));
+
+        let dir = fake_composer("show-stream-invalid", "echo '{\"locked\": ['\nexit 1")?;
+        let result = show_stream(&options(&dir), |_| {});
+        std::fs::remove_dir_all(&dir)?;
+        assert!(matches!(result, Err(Error::SerdeJsonError(_))));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_show_stream_timeout() -> Result<(), Error> {
+        // exec so killing the process closes stdout instead of leaving it
+        // open in a still running sleep
+        let dir = fake_composer(
+            "show-stream-timeout",
+            "printf '{\"locked\": ['\nexec sleep 10",
+        )?;
+        let start = Instant::now();
+        let result = show_stream(
+            &ComposerShowOptions {
+                command: ComposerCommandOptions {
+                    composer_binary: Some(dir.join("composer")),
+                    timeout: Some(Duration::from_millis(200)),
+                    ..Default::default()
+                },
+            },
+            |_| {},
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert!(
+            matches!(result, Err(Error::Timeout(_))),
+            "expected a timeout, got {:?}",
+            result
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_leading_noise() {
+        assert_eq!(
+            strip_leading_noise(r#"{"locked": []}"#),
+            r#"{"locked": []}"#
+        );
+        assert_eq!(
+            strip_leading_noise(
+                "Composer could not detect the root package (acme/website) version, defaulting to '1.0.0'.\n{\"locked\": []}\n"
+            ),
+            "{\"locked\": []}\n"
+        );
+        assert_eq!(strip_leading_noise("no json here\n"), "no json here\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tolerant_parse() -> Result<(), Error> {
+        let dir = fake_composer(
+            "tolerant-parse",
+            "echo 'Warning: the root package version could not be detected'\necho '{\"locked\": []}'",
+        )?;
+        let builder = || ComposerOutdatedOptions::builder().composer_binary(dir.join("composer"));
+        let strict = outdated(&builder().build());
+        let tolerant = outdated(&builder().tolerant_parse(true).build());
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(matches!(strict, Err(Error::SerdeJsonError(_))));
+        assert!(tolerant?.data.locked.is_empty());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_one() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let options = ComposerOutdatedOptions::builder().build();
+        let monolog = outdated_one_with(&runner, &package_name("monolog/monolog"), &options)?
+            .expect("monolog/monolog should have an update");
+        assert_eq!(monolog.latest, "3.5.0");
+        assert!(outdated_one_with(&runner, &package_name("psr/log"), &options)?.is_none());
+        assert!(outdated_one_with(&runner, &package_name("vendor/missing"), &options)?.is_none());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_cache() -> Result<(), Error> {
+        let dir =
+            std::env::temp_dir().join(format!("composer-parser-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("composer.lock"), r#"{"packages": []}"#)?;
+        let options = ComposerOutdatedOptions::builder()
+            .working_directory(&dir)
+            .cache(CachePolicy {
+                dir: dir.join("cache"),
+                ttl: Duration::from_secs(60),
+            })
+            .build();
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let failing_runner = MockRunner {
+            exit_code: 2,
+            stdout: "not json",
+            args: std::cell::RefCell::new(vec![]),
+        };
+
+        let run = outdated_with(&runner, &options)?;
+        assert_eq!(run.data.locked.len(), 5);
+
+        let cached = outdated_with(&failing_runner, &options)?;
+        assert!(failing_runner.args.borrow().is_empty());
+        assert_eq!(
+            cached.requirement,
+            IndicatedUpdateRequirement::UpdateRequired
+        );
+        assert_eq!(names(&cached.data.locked), names(&run.data.locked));
+
+        let mut other_home = options.clone();
+        other_home
+            .command
+            .env
+            .push(("COMPOSER_HOME".to_string(), "/tmp/other-home".to_string()));
+        let result = outdated_with(&failing_runner, &other_home);
+        assert!(!failing_runner.args.borrow().is_empty());
+        assert!(matches!(result, Err(Error::CommandFailed { .. })));
+        failing_runner.args.borrow_mut().clear();
+
+        let mut only = options.clone();
+        only.only_packages = package_names(["psr/log"]);
+        let filtered = outdated_with(&failing_runner, &only)?;
+        assert!(failing_runner.args.borrow().is_empty());
+        assert_eq!(names(&filtered.data.locked), vec!["psr/log"]);
+        let cached = outdated_with(&failing_runner, &options)?;
+        assert_eq!(names(&cached.data.locked), names(&run.data.locked));
+
+        std::fs::write(
+            dir.join("composer.lock"),
+            r#"{"packages": [], "content-hash": "changed"}"#,
+        )?;
+        let result = outdated_with(&failing_runner, &options);
+        assert!(!failing_runner.args.borrow().is_empty());
+        assert!(matches!(result, Err(Error::CommandFailed { .. })));
+
+        std::fs::write(dir.join("composer.lock"), r#"{"packages": []}"#)?;
+        let mut expired = options.clone();
+        expired.cache = Some(CachePolicy {
+            dir: dir.join("cache"),
+            ttl: Duration::ZERO,
+        });
+        let result = outdated_with(&failing_runner, &expired);
+        std::fs::remove_dir_all(&dir)?;
+        assert!(matches!(result, Err(Error::CommandFailed { .. })));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_unwritable_cache() -> Result<(), Error> {
+        let dir = std::env::temp_dir().join(format!(
+            "composer-parser-unwritable-cache-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("composer.lock"), r#"{"packages": []}"#)?;
+        // the cache directory can not be created below a file
+        std::fs::write(dir.join("not-a-dir"), "")?;
+        let options = ComposerOutdatedOptions::builder()
+            .working_directory(&dir)
+            .cache(CachePolicy {
+                dir: dir.join("not-a-dir").join("cache"),
+                ttl: Duration::from_secs(60),
+            })
+            .build();
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = outdated_with(&runner, &options);
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(result?.data.locked.len(), 5);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_mock_runner() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let run = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .ignore(package_name("psr/log"))
+                .build(),
+        )?;
+        assert_eq!(run.requirement, IndicatedUpdateRequirement::UpdateRequired);
+        assert_eq!(run.data.locked.len(), 5);
+        assert_eq!(
+            runner.args.borrow().last().map(String::as_str),
+            Some("psr/log")
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_mock_runner_failure() {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: "",
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = outdated_with(&runner, &ComposerOutdatedOptions::builder().build());
+        assert!(
+            matches!(result, Err(Error::CommandFailed { .. })),
+            "expected CommandFailed, got {:?}",
+            result
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_mock_runner_no_output() {
+        let runner = MockRunner {
+            exit_code: 0,
+            stdout: "\n",
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = outdated_with(&runner, &ComposerOutdatedOptions::builder().build());
+        assert!(
+            matches!(result, Err(Error::NoOutput { .. })),
+            "expected NoOutput, got {:?}",
+            result
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_only_packages() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let run = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .only_all(package_names([
+                    "psr/log",
+                    "monolog/monolog",
+                    "does/not-exist",
+                ]))
+                .build(),
+        )?;
+        assert_eq!(
+            names(run.data.locked.iter()),
+            vec!["monolog/monolog", "psr/log"]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_outdated_with_only_packages_strict() {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: SAMPLE_OUTPUT,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .only_all(package_names(["psr/log", "does/not-exist"]))
+                .only_packages_strict(true)
+                .build(),
+        );
+        assert!(
+            matches!(&result, Err(Error::UnknownPackages(unknown)) if unknown == &["does/not-exist"]),
+            "expected UnknownPackages, got {:?}",
+            result
+        );
+    }
+
+    #[cfg(all(unix, feature = "semver"))]
+    #[test]
+    fn test_outdated_with_strict_semver() -> Result<(), Error> {
+        let runner = MockRunner {
+            exit_code: 1,
+            stdout: r#"{"locked": [{"name": "vendor/package", "version": "dev-main", "latest": "dev-main", "latest-status": "up-to-date", "description": ""}]}"#,
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let run = outdated_with(&runner, &ComposerOutdatedOptions::builder().build())?;
+        assert_eq!(run.data.locked.len(), 1);
+        let result = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .strict_semver(true)
+                .build(),
+        );
+        assert!(
+            matches!(result, Err(Error::NonSemverVersion { .. })),
+            "expected NonSemverVersion, got {:?}",
+            result
+        );
         Ok(())
     }
+
+    #[cfg(all(unix, feature = "semver"))]
+    #[test]
+    fn test_outdated_with_unsupported_composer_version() {
+        let runner = MockRunner {
+            exit_code: 0,
+            stdout: "Composer version 1.10.26 2022-04-13 16:39:56\n",
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = outdated_with(
+            &runner,
+            &ComposerOutdatedOptions::builder()
+                .check_composer_version(true)
+                .build(),
+        );
+        assert!(
+            matches!(result, Err(Error::UnsupportedComposerVersion { .. })),
+            "expected UnsupportedComposerVersion, got {:?}",
+            result
+        );
+        assert_eq!(*runner.args.borrow(), vec!["--version", "--no-ansi"]);
+    }
+
+    /// the fields of the events recorded by a [CaptureLayer]
+    type CapturedEvents = std::sync::Arc<std::sync::Mutex<Vec<BTreeMap<String, String>>>>;
+
+    /// a tracing layer which records the fields of all events
+    struct CaptureLayer {
+        /// the events recorded so far
+        events: CapturedEvents,
+    }
+
+    /// collects the fields of an event as strings
+    struct FieldVisitor(BTreeMap<String, String>);
+
+    impl tracing::field::Visit for FieldVisitor {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CaptureLayer {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut visitor = FieldVisitor(BTreeMap::new());
+            event.record(&mut visitor);
+            visitor
+                .0
+                .insert("level".to_string(), event.metadata().level().to_string());
+            self.events
+                .lock()
+                .expect("capture lock should not be poisoned")
+                .push(visitor.0);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_call_logs_structured_fields() {
+        use tracing_subscriber::layer::SubscriberExt as _;
+
+        let events = CapturedEvents::default();
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer {
+            events: events.clone(),
+        });
+        let runner = MockRunner {
+            exit_code: 2,
+            stdout: "not json",
+            args: std::cell::RefCell::new(vec![]),
+        };
+        let result = tracing::subscriber::with_default(subscriber, || {
+            outdated_with(&runner, &ComposerOutdatedOptions::builder().build())
+        });
+        assert!(matches!(result, Err(Error::CommandFailed { .. })));
+
+        let events = events.lock().expect("capture lock should not be poisoned");
+        let failed = events
+            .iter()
+            .find(|e| e.get("level").map(String::as_str) == Some("WARN"))
+            .expect("the failed call should be logged as a warning");
+        assert_eq!(failed.get("command").map(String::as_str), Some("outdated"));
+        assert_eq!(failed.get("exit_code").map(String::as_str), Some("2"));
+        assert_eq!(
+            failed.get("message").map(String::as_str),
+            Some("composer did not return with a successful exit code")
+        );
+        let stdout = events
+            .iter()
+            .find(|e| e.contains_key("stdout_len"))
+            .expect("the stdout of the failed call should be logged");
+        assert_eq!(stdout.get("stdout_len").map(String::as_str), Some("8"));
+        assert_eq!(stdout.get("stdout").map(String::as_str), Some("not json"));
+    }
 }
@@ -0,0 +1,176 @@
+//! Support for parsing the JSON output of composer licenses
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, composer_command, log_failed_call, run_composer, ComposerCommandOptions, Error,
+};
+
+use std::collections::BTreeMap;
+use std::str::from_utf8;
+
+/// These are options to modify the behaviour of composer licenses
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerLicensesOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// Outer structure for parsing composer-licenses output
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ComposerLicensesData {
+    /// Name of the root package
+    pub name: Option<String>,
+    /// Version of the root package
+    pub version: Option<String>,
+    /// The licenses declared by the root package
+    #[serde(rename = "license", default)]
+    pub project_license: Vec<String>,
+    /// The licenses of all dependencies, composer reports these keyed by
+    /// package name but we flatten them into a single list
+    #[serde(deserialize_with = "deserialize_dependencies")]
+    pub dependencies: Vec<PackageLicense>,
+}
+
+/// The licenses of a single dependency
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PackageLicense {
+    /// Package name
+    pub name: String,
+    /// Package version in use
+    pub version: String,
+    /// The licenses the package is available under, packages without a
+    /// declared license are reported as `["none"]` or an empty list
+    pub license: Vec<String>,
+}
+
+impl PackageLicense {
+    /// does the package declare any license at all
+    pub fn has_license(&self) -> bool {
+        self.license.iter().any(|license| license != "none")
+    }
+}
+
+/// The shapes composer uses for the dependencies, PHP encodes an empty map as
+/// an empty list
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RawDependencies {
+    /// a flat list, this is also what we serialize to
+    List(Vec<PackageLicense>),
+    /// licenses keyed by package name
+    ByPackage(BTreeMap<String, RawPackageLicense>),
+}
+
+/// The licenses of a single dependency as composer reports them
+#[derive(serde::Deserialize)]
+struct RawPackageLicense {
+    /// Package version in use
+    version: String,
+    /// The licenses the package is available under
+    #[serde(default)]
+    license: Vec<String>,
+}
+
+/// flattens the licenses composer reports keyed by package name
+fn deserialize_dependencies<'de, D>(deserializer: D) -> Result<Vec<PackageLicense>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: RawDependencies = serde::Deserialize::deserialize(deserializer)?;
+    Ok(match raw {
+        RawDependencies::List(dependencies) => dependencies,
+        RawDependencies::ByPackage(by_package) => by_package
+            .into_iter()
+            .map(|(name, raw)| PackageLicense {
+                name,
+                version: raw.version,
+                license: raw.license,
+            })
+            .collect(),
+    })
+}
+
+/// main entry point for the composer-licenses call
+pub fn licenses(options: &ComposerLicensesOptions) -> Result<ComposerLicensesData, Error> {
+    let mut cmd = composer_command(&options.command)?;
+
+    cmd.args(["licenses", "--format=json"])
+        .args(plugin_args(&options.command));
+
+    let output = run_composer(cmd, &options.command)?;
+
+    if !output.status.success() {
+        log_failed_call("licenses", &output);
+        return Err(command_failed(&output));
+    }
+
+    let json_str = from_utf8(&output.stdout)?;
+    let data: ComposerLicensesData = serde_json::from_str(json_str)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_licenses_output() -> Result<(), Error> {
+        let json = r#"{
+            "name": "acme/website",
+            "version": "dev-main",
+            "license": [
+                "proprietary"
+            ],
+            "dependencies": {
+                "monolog/monolog": {
+                    "version": "3.5.0",
+                    "license": [
+                        "MIT"
+                    ]
+                },
+                "acme/internal": {
+                    "version": "1.0.0",
+                    "license": [
+                        "none"
+                    ]
+                },
+                "symfony/polyfill-mbstring": {
+                    "version": "v1.28.0",
+                    "license": [
+                        "MIT",
+                        "Apache-2.0"
+                    ]
+                }
+            }
+        }"#;
+        let data: ComposerLicensesData = serde_json::from_str(json)?;
+        assert_eq!(data.name.as_deref(), Some("acme/website"));
+        assert_eq!(data.project_license, vec!["proprietary"]);
+        assert_eq!(data.dependencies.len(), 3);
+        assert_eq!(data.dependencies[0].name, "acme/internal");
+        assert_eq!(data.dependencies[0].license, vec!["none"]);
+        assert!(!data.dependencies[0].has_license());
+        assert_eq!(data.dependencies[1].name, "monolog/monolog");
+        assert!(data.dependencies[1].has_license());
+        assert_eq!(data.dependencies[2].license, vec!["MIT", "Apache-2.0"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_licenses_output_without_dependencies() -> Result<(), Error> {
+        let json = r#"{
+            "name": "acme/empty",
+            "version": "dev-main",
+            "license": [],
+            "dependencies": []
+        }"#;
+        let data: ComposerLicensesData = serde_json::from_str(json)?;
+        assert!(data.project_license.is_empty());
+        assert!(data.dependencies.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! Support for reading composer.lock directly without calling composer
+
+use crate::{ComposerOutdatedData, Error, PackageSource};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The parts of composer.lock we are interested in
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct LockFile {
+    /// The locked packages required in production
+    pub packages: Vec<LockedPackage>,
+    /// The locked packages only required for development
+    #[serde(rename = "packages-dev", default)]
+    pub packages_dev: Vec<LockedPackage>,
+}
+
+/// A single locked package from composer.lock
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct LockedPackage {
+    /// Package name
+    pub name: String,
+    /// Package version that is locked
+    pub version: String,
+    /// The kind of package, e.g. `library` or `composer-plugin`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub package_type: Option<String>,
+    /// Where the source of this package can be obtained from, e.g. a VCS
+    /// repository, packages only available as archives have none
+    #[serde(default)]
+    pub source: Option<PackageReference>,
+    /// Where an archive of this package can be obtained from, packages only
+    /// available from a VCS repository have none
+    #[serde(default)]
+    pub dist: Option<PackageReference>,
+}
+
+/// The exact location a locked package is pinned to, either its `source`
+/// (e.g. a git repository and commit) or its `dist` (e.g. a zip archive)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PackageReference {
+    /// The kind of source or archive, e.g. `git`, `zip` or `path`
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    /// Where the source or archive can be found
+    pub url: String,
+    /// The revision pinned, e.g. a commit hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// The SHA-1 checksum of the archive, composer writes an empty string
+    /// if it is unknown which is represented as None
+    #[serde(
+        default,
+        deserialize_with = "deserialize_shasum",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub shasum: Option<String>,
+}
+
+/// composer writes an empty string or null for an unknown checksum
+fn deserialize_shasum<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let shasum: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(shasum.filter(|shasum| !shasum.is_empty()))
+}
+
+/// reads and parses a composer.lock file, this does not require composer to
+/// be installed
+pub fn parse_lock_file(path: &Path) -> Result<LockFile, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let lock_file: LockFile = serde_json::from_str(&content)?;
+    Ok(lock_file)
+}
+
+/// sets [is_dev](crate::PackageStatus::is_dev) on all packages in the
+/// outdated data which can be found in the lock file
+///
+/// this is best-effort, packages which are not in the lock file keep their
+/// previous value
+pub fn annotate_dev(data: &mut ComposerOutdatedData, lock_file: &LockFile) {
+    let is_dev: BTreeMap<&str, bool> = lock_file
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), false))
+        .chain(
+            lock_file
+                .packages_dev
+                .iter()
+                .map(|p| (p.name.as_str(), true)),
+        )
+        .collect();
+    for package in &mut data.locked {
+        if let Some(is_dev) = is_dev.get(package.name.as_str()) {
+            package.is_dev = Some(*is_dev);
+        }
+    }
+}
+
+/// the source of a locked package in the form used in the outdated data
+fn package_source(package: &LockedPackage) -> Option<PackageSource> {
+    let source = package.source.as_ref()?;
+    Some(PackageSource {
+        source_type: Some(source.reference_type.clone()),
+        url: source.url.clone(),
+        reference: source.reference.clone(),
+    })
+}
+
+/// sets [source](crate::PackageStatus::source) on all packages in the
+/// outdated data which can be found in the lock file with a source
+///
+/// this is best-effort, packages which are not in the lock file or have no
+/// source there keep their previous value
+pub fn annotate_source(data: &mut ComposerOutdatedData, lock_file: &LockFile) {
+    let locked: BTreeMap<&str, &LockedPackage> = lock_file
+        .packages
+        .iter()
+        .chain(lock_file.packages_dev.iter())
+        .map(|p| (p.name.as_str(), p))
+        .collect();
+    for package in &mut data.locked {
+        if let Some(source) = locked
+            .get(package.name.as_str())
+            .and_then(|p| package_source(p))
+        {
+            package.source = Some(source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// a shortened composer.lock as written by composer 2
+    const SAMPLE_LOCK_FILE: &str = r#"{
+        "_readme": [
+            "This file locks the dependencies of your project to a known state",
+            "This file is @generated automatically"
+        ],
+        "content-hash": "0c5d1e8d4b7d9e2f5c1f1a1b39e2c6e4",
+        "packages": [
+            {
+                "name": "monolog/monolog",
+                "version": "3.5.0",
+                "source": {
+                    "type": "git",
+                    "url": "https://github.com/Seldaek/monolog.git",
+                    "reference": "c915e2634718dbc8a4a15c61b0e62e7a44e14448"
+                },
+                "dist": {
+                    "type": "zip",
+                    "url": "https://api.github.com/repos/Seldaek/monolog/zipball/c915e2634718dbc8a4a15c61b0e62e7a44e14448",
+                    "reference": "c915e2634718dbc8a4a15c61b0e62e7a44e14448",
+                    "shasum": ""
+                },
+                "require": {
+                    "php": ">=8.1",
+                    "psr/log": "^2.0 || ^3.0"
+                },
+                "type": "library"
+            }
+        ],
+        "packages-dev": [
+            {
+                "name": "phpunit/phpunit",
+                "version": "10.5.5",
+                "source": {
+                    "type": "git",
+                    "url": "https://github.com/sebastianbergmann/phpunit.git",
+                    "reference": "ed21115d505b4b4f7dc7b5651464e19a2c7f7856"
+                },
+                "type": "library"
+            }
+        ],
+        "aliases": [],
+        "minimum-stability": "stable",
+        "plugin-api-version": "2.6.0"
+    }"#;
+
+    #[test]
+    fn test_parse_lock_file() -> Result<(), Error> {
+        let dir = std::env::temp_dir().join(format!("composer-parser-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("composer.lock");
+        std::fs::write(&path, SAMPLE_LOCK_FILE)?;
+        let result = parse_lock_file(&path);
+        std::fs::remove_dir_all(&dir)?;
+        let lock_file = result?;
+        assert_eq!(lock_file.packages.len(), 1);
+        assert_eq!(lock_file.packages[0].name, "monolog/monolog");
+        assert_eq!(lock_file.packages[0].version, "3.5.0");
+        assert_eq!(
+            lock_file.packages[0].package_type.as_deref(),
+            Some("library")
+        );
+        assert_eq!(
+            lock_file.packages[0].source,
+            Some(PackageReference {
+                reference_type: "git".to_string(),
+                url: "https://github.com/Seldaek/monolog.git".to_string(),
+                reference: Some("c915e2634718dbc8a4a15c61b0e62e7a44e14448".to_string()),
+                shasum: None,
+            })
+        );
+        assert_eq!(
+            lock_file.packages[0]
+                .dist
+                .as_ref()
+                .map(|dist| (dist.reference_type.as_str(), dist.shasum.as_deref())),
+            Some(("zip", None))
+        );
+        assert_eq!(lock_file.packages_dev.len(), 1);
+        assert_eq!(lock_file.packages_dev[0].name, "phpunit/phpunit");
+        assert!(lock_file.packages_dev[0].dist.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_dist_only_package() -> Result<(), Error> {
+        let package: LockedPackage = serde_json::from_str(
+            r#"{
+                "name": "vendor/archive",
+                "version": "1.0.0",
+                "dist": {
+                    "type": "tar",
+                    "url": "https://example.com/archive-1.0.0.tar.gz",
+                    "shasum": "0a3b8f5b8c0e0a4fcee0e2f1a24c35d4a1cbc3c6"
+                }
+            }"#,
+        )?;
+        assert_eq!(package.source, None);
+        assert_eq!(
+            package.dist,
+            Some(PackageReference {
+                reference_type: "tar".to_string(),
+                url: "https://example.com/archive-1.0.0.tar.gz".to_string(),
+                reference: None,
+                shasum: Some("0a3b8f5b8c0e0a4fcee0e2f1a24c35d4a1cbc3c6".to_string()),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_missing_lock_file() {
+        let result = parse_lock_file(Path::new("this-file-does-not-exist.lock"));
+        assert!(matches!(result, Err(Error::StdIoError(_))));
+    }
+
+    #[test]
+    fn test_annotate_dev() -> Result<(), Error> {
+        let lock_file: LockFile = serde_json::from_str(SAMPLE_LOCK_FILE)?;
+        let mut data: ComposerOutdatedData = serde_json::from_str(
+            r#"{
+                "locked": [
+                    {
+                        "name": "monolog/monolog",
+                        "version": "3.5.0",
+                        "latest": "3.5.0",
+                        "latest-status": "up-to-date",
+                        "description": "Sends your logs to files, sockets, inboxes, databases and various web services"
+                    },
+                    {
+                        "name": "phpunit/phpunit",
+                        "version": "10.5.5",
+                        "latest": "11.0.0",
+                        "latest-status": "update-possible",
+                        "description": "The PHP Unit Testing framework."
+                    },
+                    {
+                        "name": "unknown/package",
+                        "version": "1.0.0",
+                        "latest": "1.0.0",
+                        "latest-status": "up-to-date",
+                        "description": ""
+                    }
+                ]
+            }"#,
+        )?;
+        assert_eq!(data.locked[0].is_dev, None);
+        annotate_dev(&mut data, &lock_file);
+        let is_dev: Vec<Option<bool>> = data.locked.iter().map(|p| p.is_dev).collect();
+        assert_eq!(is_dev, vec![Some(false), Some(true), None]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotate_source() -> Result<(), Error> {
+        let lock_file: LockFile = serde_json::from_str(SAMPLE_LOCK_FILE)?;
+        let mut data: ComposerOutdatedData = serde_json::from_str(
+            r#"{
+                "locked": [
+                    {
+                        "name": "monolog/monolog",
+                        "source": "https://github.com/Seldaek/monolog/tree/3.5.0",
+                        "version": "3.5.0",
+                        "latest": "3.5.0",
+                        "latest-status": "up-to-date",
+                        "description": ""
+                    },
+                    {
+                        "name": "unknown/package",
+                        "version": "1.0.0",
+                        "latest": "1.0.0",
+                        "latest-status": "up-to-date",
+                        "description": ""
+                    }
+                ]
+            }"#,
+        )?;
+        assert_eq!(
+            data.locked[0].source,
+            Some(PackageSource {
+                source_type: None,
+                url: "https://github.com/Seldaek/monolog/tree/3.5.0".to_string(),
+                reference: None,
+            })
+        );
+        annotate_source(&mut data, &lock_file);
+        assert_eq!(
+            data.locked[0].source,
+            Some(PackageSource {
+                source_type: Some("git".to_string()),
+                url: "https://github.com/Seldaek/monolog.git".to_string(),
+                reference: Some("c915e2634718dbc8a4a15c61b0e62e7a44e14448".to_string()),
+            })
+        );
+        assert_eq!(data.locked[1].source, None);
+        let json = serde_json::to_string(&data)?;
+        let round_tripped: ComposerOutdatedData = serde_json::from_str(&json)?;
+        assert_eq!(round_tripped.locked[0].source, data.locked[0].source);
+        Ok(())
+    }
+}
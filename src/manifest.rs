@@ -0,0 +1,88 @@
+//! Support for reading the declared dependencies from composer.json
+
+use crate::Error;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The parts of composer.json we are interested in
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    /// Package name, this is optional for root packages which are not
+    /// published
+    pub name: Option<String>,
+    /// Description for the package
+    pub description: Option<String>,
+    /// The version constraints for the packages required in production,
+    /// keyed by package name
+    #[serde(default)]
+    pub require: BTreeMap<String, String>,
+    /// The version constraints for the packages only required for
+    /// development, keyed by package name
+    #[serde(rename = "require-dev", default)]
+    pub require_dev: BTreeMap<String, String>,
+}
+
+/// reads and parses a composer.json file, this does not require composer to
+/// be installed
+pub fn parse_manifest(path: &Path) -> Result<Manifest, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let manifest: Manifest = serde_json::from_str(&content)?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_manifest() -> Result<(), Error> {
+        let dir =
+            std::env::temp_dir().join(format!("composer-parser-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("composer.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "name": "acme/website",
+                "description": "The Acme website",
+                "type": "project",
+                "require": {
+                    "php": ">=8.1",
+                    "monolog/monolog": "^3.0"
+                },
+                "require-dev": {
+                    "phpunit/phpunit": "^10.5"
+                }
+            }"#,
+        )?;
+        let result = parse_manifest(&path);
+        std::fs::remove_dir_all(&dir)?;
+        let manifest = result?;
+        assert_eq!(manifest.name.as_deref(), Some("acme/website"));
+        assert_eq!(manifest.description.as_deref(), Some("The Acme website"));
+        assert_eq!(
+            manifest.require.get("monolog/monolog").map(String::as_str),
+            Some("^3.0")
+        );
+        assert_eq!(manifest.require.len(), 2);
+        assert_eq!(
+            manifest
+                .require_dev
+                .get("phpunit/phpunit")
+                .map(String::as_str),
+            Some("^10.5")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_manifest_without_requirements() -> Result<(), Error> {
+        let manifest: Manifest = serde_json::from_str("{}")?;
+        assert_eq!(manifest.name, None);
+        assert!(manifest.require.is_empty());
+        assert!(manifest.require_dev.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,479 @@
+//! Validated composer package names
+
+use crate::{Error, PackageStatus};
+
+/// A composer package name in the `vendor/name` form
+///
+/// Both parts consist of lowercase letters and digits, optionally separated
+/// by single `_`, `.` or `-` characters, the name part also allows `--`,
+/// these are the same rules composer enforces for package names
+///
+/// The names of the platform packages composer provides for the environment
+/// are accepted as well even though they have no vendor, see
+/// [is_platform](PackageName::is_platform)
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct PackageName(String);
+
+/// checks one part of a package name, the vendor if double_dash is false and
+/// the name otherwise
+fn is_valid_name_part(part: &str, double_dash: bool) -> bool {
+    let is_alphanumeric = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+    let mut chars = part.chars().peekable();
+    if !chars.next().is_some_and(is_alphanumeric) {
+        return false;
+    }
+    while let Some(c) = chars.next() {
+        if is_alphanumeric(c) {
+            continue;
+        }
+        let is_separator = match c {
+            '_' | '.' => true,
+            '-' => {
+                if double_dash && chars.peek() == Some(&'-') {
+                    chars.next();
+                }
+                true
+            }
+            _ => false,
+        };
+        // every separator has to be followed by a letter or digit
+        if !is_separator || !chars.next().is_some_and(is_alphanumeric) {
+            return false;
+        }
+    }
+    true
+}
+
+/// the platform packages without a prefix
+const PLATFORM_PACKAGES: [&str; 7] = [
+    "php",
+    "php-64bit",
+    "php-ipv6",
+    "php-zts",
+    "php-debug",
+    "hhvm",
+    "composer",
+];
+
+/// the prefixes of the platform packages for PHP extensions, libraries and
+/// the APIs composer provides like `composer-plugin-api`
+const PLATFORM_PREFIXES: [&str; 3] = ["ext-", "lib-", "composer-"];
+
+/// is this the name of a platform package
+fn is_platform_name(name: &str) -> bool {
+    PLATFORM_PACKAGES.contains(&name)
+        || PLATFORM_PREFIXES.iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| is_valid_name_part(rest, false))
+        })
+}
+
+/// is this a valid package name, either vendor/name or a platform package
+fn is_valid_package_name(name: &str) -> bool {
+    is_platform_name(name)
+        || name.split_once('/').is_some_and(|(vendor, package)| {
+            is_valid_name_part(vendor, false) && is_valid_name_part(package, true)
+        })
+}
+
+impl PackageName {
+    /// validates the given package name
+    pub fn new(name: impl Into<String>) -> Result<Self, Error> {
+        let name = name.into();
+        if is_valid_package_name(&name) {
+            Ok(PackageName(name))
+        } else {
+            Err(Error::InvalidPackageName(name))
+        }
+    }
+
+    /// the package name as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// the vendor part of the package name, empty for platform packages
+    pub fn vendor(&self) -> &str {
+        self.0.split_once('/').map_or("", |(vendor, _)| vendor)
+    }
+
+    /// is this a platform package composer provides for the environment
+    /// instead of a real package, these are `php` (and its variants
+    /// `php-64bit`, `php-ipv6`, `php-zts` and `php-debug`), `hhvm`,
+    /// `composer`, the APIs of composer prefixed with `composer-` like
+    /// `composer-plugin-api` and `composer-runtime-api` and the extensions
+    /// and libraries prefixed with `ext-` and `lib-`
+    pub fn is_platform(&self) -> bool {
+        !self.0.contains('/')
+    }
+}
+
+impl std::fmt::Display for PackageName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for PackageName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PackageName::new(s)
+    }
+}
+
+impl TryFrom<String> for PackageName {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        PackageName::new(name)
+    }
+}
+
+impl From<PackageName> for String {
+    fn from(name: PackageName) -> Self {
+        name.0
+    }
+}
+
+impl AsRef<str> for PackageName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for PackageName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PackageName {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// The identity of a package by name only, e.g. to deduplicate packages or
+/// use them as keys in sets and maps
+///
+/// [PackageStatus] itself deliberately has no equality since comparing
+/// records is ambiguous, two statuses of the same package with different
+/// versions are different records but the same package, this makes the
+/// name-only comparison explicit
+///
+/// ```
+/// use composer_parser::parse_outdated_json;
+/// use std::collections::HashSet;
+///
+/// let data = parse_outdated_json(r#"{"locked": [
+///     {"name": "psr/log", "version": "2.0.0", "latest": "3.0.0",
+///      "latest-status": "update-possible", "description": ""},
+///     {"name": "psr/log", "version": "3.0.0", "latest": "3.0.0",
+///      "latest-status": "up-to-date", "description": ""}
+/// ]}"#)?;
+/// let keys: HashSet<_> = data.locked.iter().map(|p| p.key()).collect();
+/// assert_eq!(keys.len(), 1);
+/// # Ok::<(), composer_parser::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageKey<'a>(pub &'a str);
+
+impl std::fmt::Display for PackageKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PackageStatus {
+    /// the name-only identity of the package, see [PackageKey]
+    pub fn key(&self) -> PackageKey<'_> {
+        PackageKey(self.name.as_str())
+    }
+}
+
+/// A package name which may contain `*` wildcards matching any sequence of
+/// characters, e.g. `symfony/*` or `symfony/polyfill-*`, as accepted by
+/// composer outdated --ignore
+///
+/// With every `*` replaced by a letter the pattern has to be a valid
+/// [PackageName], every package name is a pattern matching only itself
+///
+/// ```
+/// use composer_parser::IgnorePattern;
+///
+/// let pattern = IgnorePattern::new("symfony/polyfill-*")?;
+/// assert!(pattern.matches("symfony/polyfill-mbstring"));
+/// assert!(!pattern.matches("symfony/console"));
+/// assert!(IgnorePattern::new("Symfony/*").is_err());
+/// # Ok::<(), composer_parser::Error>(())
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct IgnorePattern(String);
+
+impl IgnorePattern {
+    /// validates the given pattern
+    pub fn new(pattern: impl Into<String>) -> Result<Self, Error> {
+        let pattern = pattern.into();
+        if is_valid_package_name(&pattern.replace('*', "x")) {
+            Ok(IgnorePattern(pattern))
+        } else {
+            Err(Error::InvalidPackageName(pattern))
+        }
+    }
+
+    /// the pattern as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// does the pattern match the given package name
+    pub fn matches(&self, name: &str) -> bool {
+        let mut parts = self.0.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = name.strip_prefix(first) else {
+            return false;
+        };
+        let mut parts: Vec<&str> = parts.collect();
+        let Some(last) = parts.pop() else {
+            // no wildcard at all
+            return rest.is_empty();
+        };
+        for part in parts {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+}
+
+impl From<PackageName> for IgnorePattern {
+    fn from(name: PackageName) -> Self {
+        IgnorePattern(name.0)
+    }
+}
+
+impl std::fmt::Display for IgnorePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for IgnorePattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IgnorePattern::new(s)
+    }
+}
+
+impl TryFrom<String> for IgnorePattern {
+    type Error = Error;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        IgnorePattern::new(pattern)
+    }
+}
+
+impl From<IgnorePattern> for String {
+    fn from(pattern: IgnorePattern) -> Self {
+        pattern.0
+    }
+}
+
+impl AsRef<str> for IgnorePattern {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for IgnorePattern {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for IgnorePattern {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::UpdateRequirement;
+    use pretty_assertions::assert_eq;
+
+    /// creates a package status with the given name, versions and update
+    /// requirement and everything else empty, for the tests of the methods
+    /// on single packages
+    pub(crate) fn test_package(
+        name: &str,
+        version: &str,
+        latest: &str,
+        latest_status: UpdateRequirement,
+    ) -> PackageStatus {
+        PackageStatus {
+            name: PackageName::new(name).expect("package name should be valid"),
+            version: version.to_string(),
+            latest: latest.to_string(),
+            latest_status,
+            description: String::new(),
+            warning: None,
+            is_dev: None,
+            source: None,
+            homepage: None,
+            support: None,
+        }
+    }
+
+    #[test]
+    fn test_package_key() {
+        let installed = test_package(
+            "psr/log",
+            "2.0.0",
+            "3.0.0",
+            UpdateRequirement::UpdatePossible,
+        );
+        let updated = test_package("psr/log", "3.0.0", "3.0.0", UpdateRequirement::UpToDate);
+        assert_eq!(installed.key(), updated.key());
+        assert_eq!(installed.key().to_string(), "psr/log");
+        assert_ne!(
+            installed.key(),
+            test_package(
+                "psr/container",
+                "2.0.0",
+                "2.0.0",
+                UpdateRequirement::UpToDate
+            )
+            .key()
+        );
+    }
+
+    #[test]
+    fn test_valid_package_names() -> Result<(), Error> {
+        for name in [
+            "psr/log",
+            "symfony/polyfill-mbstring",
+            "doctrine/instantiator",
+            "php-http/message-factory",
+            "vendor.name/package_name",
+            "vendor/package--name",
+            "0x/1.2",
+        ] {
+            let package_name = PackageName::new(name)?;
+            assert_eq!(package_name.as_str(), name);
+            assert!(!package_name.is_platform());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_platform_package_names() -> Result<(), Error> {
+        for name in [
+            "php",
+            "php-64bit",
+            "hhvm",
+            "ext-json",
+            "ext-zend-opcache",
+            "lib-icu-uc",
+            "composer",
+            "composer-plugin-api",
+            "composer-runtime-api",
+            "composer-api",
+        ] {
+            let package_name = PackageName::new(name)?;
+            assert!(
+                package_name.is_platform(),
+                "{} should be a platform package",
+                name
+            );
+            assert_eq!(package_name.vendor(), "");
+        }
+        for name in [
+            "ext-",
+            "ext-JSON",
+            "lib--icu",
+            "php-foo",
+            "composer-",
+            "composer-Plugin-api",
+        ] {
+            assert!(
+                PackageName::new(name).is_err(),
+                "expected {:?} to be rejected",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_package_names() {
+        for name in [
+            "",
+            "psr",
+            "psr/",
+            "/log",
+            "psr/log/extra",
+            "Psr/Log",
+            "psr/log-",
+            "psr/-log",
+            "vendor--name/package",
+            "psr/log__extra",
+            "psr/log name",
+        ] {
+            assert!(
+                matches!(PackageName::new(name), Err(Error::InvalidPackageName(_))),
+                "expected {:?} to be rejected",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_package_name_serde() -> Result<(), Error> {
+        let name: PackageName = serde_json::from_str("\"psr/log\"")?;
+        assert_eq!(name, "psr/log");
+        assert_eq!(name.vendor(), "psr");
+        assert_eq!(serde_json::to_string(&name)?, "\"psr/log\"");
+        assert!(serde_json::from_str::<PackageName>("\"psr\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<PackageName>("\"ext-json\"")?,
+            "ext-json"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_patterns() -> Result<(), Error> {
+        let pattern = IgnorePattern::new("symfony/*")?;
+        assert!(pattern.matches("symfony/console"));
+        assert!(!pattern.matches("doctrine/orm"));
+        let pattern = IgnorePattern::new("*/polyfill-*-php8*")?;
+        assert!(pattern.matches("symfony/polyfill-intl-php80"));
+        assert!(!pattern.matches("symfony/polyfill-mbstring"));
+        let pattern: IgnorePattern = PackageName::new("psr/log")?.into();
+        assert!(pattern.matches("psr/log"));
+        assert!(!pattern.matches("psr/log-extra"));
+        assert!(IgnorePattern::new("ext-*")?.matches("ext-json"));
+        for invalid in ["*", "Symfony/*", "symfony/* console", "symfony", ""] {
+            assert!(
+                IgnorePattern::new(invalid).is_err(),
+                "{invalid:?} should be invalid"
+            );
+        }
+        let pattern: IgnorePattern = serde_json::from_str(r#""symfony/*""#)?;
+        assert_eq!(pattern, "symfony/*");
+        assert!(serde_json::from_str::<IgnorePattern>(r#""a b/*""#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Control over which composer plugins may run
+
+use crate::{lock_file_path, parse_lock_file, ComposerCommandOptions, Error};
+
+/// Which composer plugins are enabled when running composer
+///
+/// Plugins are arbitrary PHP code installed with the dependencies, enabling
+/// them means running that code with the permissions of this process, so
+/// they are disabled by default, some projects need a plugin e.g. to resolve
+/// packages from a private repository though
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PluginMode {
+    /// Disable all plugins (composer's `--no-plugins`)
+    #[default]
+    None,
+    /// Enable all plugins composer's own `allow-plugins` configuration allows
+    All,
+    /// Enable plugins only if all plugins in composer.lock are on this list
+    /// of package names, a name ending in `*` allows all packages starting
+    /// with the part before it, composer has no flag to enable just some
+    /// plugins so if any other plugin is installed an error is returned
+    /// instead of running composer, plugins installed globally in the
+    /// composer home directory are not checked
+    Allow(Vec<String>),
+}
+
+/// parses the plugins command line argument, either `none`, `all` or a comma
+/// separated list of allowed plugin packages
+pub(crate) fn parse_plugin_mode(s: &str) -> Result<PluginMode, String> {
+    match s {
+        "none" => Ok(PluginMode::None),
+        "all" => Ok(PluginMode::All),
+        _ => {
+            let names: Vec<String> = s
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+            if names.is_empty() {
+                return Err(format!("expected none, all or plugin names but got {}", s));
+            }
+            Ok(PluginMode::Allow(names))
+        }
+    }
+}
+
+/// the arguments to pass to composer for the plugin mode
+pub(crate) fn plugin_args(options: &ComposerCommandOptions) -> &'static [&'static str] {
+    match options.plugins {
+        PluginMode::None => &["--no-plugins"],
+        PluginMode::All | PluginMode::Allow(_) => &[],
+    }
+}
+
+/// is the plugin package allowed by one of the patterns
+fn is_allowed(allowed: &[String], name: &str) -> bool {
+    allowed
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => pattern == name,
+        })
+}
+
+/// returns an error if the plugin mode only allows some plugins and the
+/// composer.lock in the working directory contains any other plugin
+pub(crate) fn check_allowed_plugins(options: &ComposerCommandOptions) -> Result<(), Error> {
+    let PluginMode::Allow(allowed) = &options.plugins else {
+        return Ok(());
+    };
+    let lock_file_path = lock_file_path(options);
+    let lock_file = match parse_lock_file(&lock_file_path) {
+        Ok(lock_file) => lock_file,
+        // without a lock file there are no installed plugins
+        Err(Error::StdIoError(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(());
+        }
+        Err(err) => return Err(err),
+    };
+    let not_allowed: Vec<String> = lock_file
+        .packages
+        .iter()
+        .chain(lock_file.packages_dev.iter())
+        .filter(|p| p.package_type.as_deref() == Some("composer-plugin"))
+        .filter(|p| !is_allowed(allowed, &p.name))
+        .map(|p| p.name.clone())
+        .collect();
+    if not_allowed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::PluginNotAllowed(not_allowed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_plugin_mode() {
+        assert_eq!(parse_plugin_mode("none"), Ok(PluginMode::None));
+        assert_eq!(parse_plugin_mode("all"), Ok(PluginMode::All));
+        assert_eq!(
+            parse_plugin_mode("vendor/plugin, other/*"),
+            Ok(PluginMode::Allow(vec![
+                "vendor/plugin".to_string(),
+                "other/*".to_string()
+            ]))
+        );
+        assert!(parse_plugin_mode(",").is_err());
+    }
+
+    #[test]
+    fn test_check_allowed_plugins() -> Result<(), Error> {
+        let dir =
+            std::env::temp_dir().join(format!("composer-parser-plugins-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(
+            dir.join("composer.lock"),
+            r#"{
+                "packages": [
+                    {"name": "psr/log", "version": "3.0.0", "type": "library"},
+                    {"name": "php-http/discovery", "version": "1.19.2", "type": "composer-plugin"},
+                    {"name": "acme/repository-plugin", "version": "1.0.0", "type": "composer-plugin"}
+                ]
+            }"#,
+        )?;
+        let options = |allowed: &[&str]| ComposerCommandOptions {
+            working_directory: Some(dir.clone()),
+            plugins: PluginMode::Allow(allowed.iter().map(|s| s.to_string()).collect()),
+            ..Default::default()
+        };
+        let all_allowed = check_allowed_plugins(&options(&["php-http/discovery", "acme/*"]));
+        let some_allowed = check_allowed_plugins(&options(&["acme/repository-plugin"]));
+        std::fs::remove_dir_all(&dir)?;
+
+        all_allowed?;
+        assert!(
+            matches!(&some_allowed, Err(Error::PluginNotAllowed(names)) if names == &["php-http/discovery"]),
+            "expected PluginNotAllowed, got {:?}",
+            some_allowed
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_plugin_args() {
+        assert_eq!(
+            plugin_args(&ComposerCommandOptions::default()),
+            &["--no-plugins"]
+        );
+        let options = ComposerCommandOptions {
+            plugins: PluginMode::All,
+            ..Default::default()
+        };
+        assert!(plugin_args(&options).is_empty());
+    }
+}
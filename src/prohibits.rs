@@ -0,0 +1,111 @@
+//! Support for parsing the text output of composer prohibits (also known as
+//! composer why-not)
+
+use crate::depends::parse_depends_output;
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, log_failed_call, run_composer, text_composer_command, ComposerCommandOptions,
+    Error,
+};
+
+use std::str::from_utf8;
+
+/// These are options to modify the behaviour of composer prohibits
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerProhibitsOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// A single reason why a package can not be updated to a version
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DependencyConflict {
+    /// Name of the package preventing the update
+    pub package: String,
+    /// Version of the package preventing the update, composer does not
+    /// report one for the root package
+    pub version: Option<String>,
+    /// The constraint of the package that prevents the update
+    pub constraint: String,
+    /// Why the constraint prevents the update, e.g. `requires` or `conflicts`
+    pub reason: String,
+}
+
+/// parses the text output of composer prohibits
+fn parse_prohibits_output(output: &str) -> Vec<DependencyConflict> {
+    parse_depends_output(output)
+        .into_iter()
+        .map(|link| DependencyConflict {
+            package: link.dependent,
+            version: link.version,
+            constraint: link.constraint,
+            reason: link.relation,
+        })
+        .collect()
+}
+
+/// main entry point for the composer-prohibits call, returns the packages
+/// which prevent the given package from being updated to the given version
+pub fn why_not(
+    package: &str,
+    version: &str,
+    options: &ComposerProhibitsOptions,
+) -> Result<Vec<DependencyConflict>, Error> {
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.arg("prohibits")
+        .args(plugin_args(&options.command))
+        .args(["--locked", package, version]);
+
+    let output = run_composer(cmd, &options.command)?;
+
+    let conflicts = parse_prohibits_output(from_utf8(&output.stdout)?);
+
+    // composer exits with an unsuccessful exit code if something prohibits
+    // the update so this is only a failure if there is no output
+    if !output.status.success() && conflicts.is_empty() {
+        log_failed_call("prohibits", &output);
+        return Err(command_failed(&output));
+    }
+
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_prohibits_output() {
+        let output = "\
+laravel/framework v10.48.4 requires         symfony/console (^6.2)
+acme/website      dev-main does not require symfony/console (but v6.4.1 is installed)
+";
+        assert_eq!(
+            parse_prohibits_output(output),
+            vec![
+                DependencyConflict {
+                    package: "laravel/framework".to_string(),
+                    version: Some("v10.48.4".to_string()),
+                    constraint: "^6.2".to_string(),
+                    reason: "requires".to_string(),
+                },
+                DependencyConflict {
+                    package: "acme/website".to_string(),
+                    version: Some("dev-main".to_string()),
+                    constraint: "but v6.4.1 is installed".to_string(),
+                    reason: "does not require".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_prohibits_output_empty() {
+        assert_eq!(parse_prohibits_output(""), vec![]);
+    }
+}
@@ -0,0 +1,99 @@
+//! Discovery of the root directory of a composer project
+
+use crate::ComposerCommandOptions;
+
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// finds the root of the composer project containing the given path by
+/// walking up the parent directories looking for a composer.json like
+/// composer itself does, if there are several a directory which also has a
+/// composer.lock is preferred over a nearer one which does not (e.g. a
+/// package inside a monorepo), returns None if there is no composer.json in
+/// any of the directories up to the root of the filesystem
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    let start = std::path::absolute(start).ok()?;
+    let mut nearest = None;
+    for dir in start.ancestors() {
+        if !dir.join("composer.json").is_file() {
+            continue;
+        }
+        if dir.join("composer.lock").is_file() {
+            return Some(dir.to_path_buf());
+        }
+        if nearest.is_none() {
+            nearest = Some(dir.to_path_buf());
+        }
+    }
+    nearest
+}
+
+/// the directory composer is run in and composer.json and composer.lock are
+/// read from, the [working_directory](ComposerCommandOptions::working_directory)
+/// or the current directory, or the project root containing it if
+/// [discover_project_root](ComposerCommandOptions::discover_project_root) is
+/// set and one can be found
+pub(crate) fn project_directory(options: &ComposerCommandOptions) -> PathBuf {
+    let dir = options
+        .working_directory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    if options.discover_project_root && !options.global {
+        match find_project_root(&dir) {
+            Some(root) => {
+                debug!(root = %root.display(), "discovered composer project root");
+                return root;
+            }
+            None => debug!(start = %dir.display(), "no composer project root found"),
+        }
+    }
+    dir
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_find_project_root() -> Result<(), std::io::Error> {
+        let dir = std::env::temp_dir().join(format!(
+            "composer-parser-project-root-{}",
+            std::process::id()
+        ));
+        let package = dir.join("packages").join("library");
+        let nested = package.join("src").join("Command");
+        std::fs::create_dir_all(&nested)?;
+        std::fs::write(package.join("composer.json"), "{}")?;
+        let nearest = find_project_root(&nested);
+        std::fs::write(dir.join("composer.json"), "{}")?;
+        std::fs::write(dir.join("composer.lock"), "{}")?;
+        let with_lock = find_project_root(&nested);
+        let from_root = find_project_root(&dir);
+        std::fs::remove_dir_all(&dir)?;
+        assert_eq!(nearest, Some(package.clone()));
+        assert_eq!(with_lock, Some(dir.clone()));
+        assert_eq!(from_root, Some(dir));
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_directory() {
+        let options = ComposerCommandOptions {
+            working_directory: Some(PathBuf::from("/nonexistent/project")),
+            ..Default::default()
+        };
+        assert_eq!(
+            project_directory(&options),
+            PathBuf::from("/nonexistent/project")
+        );
+        let options = ComposerCommandOptions {
+            discover_project_root: true,
+            ..options
+        };
+        assert_eq!(
+            project_directory(&options),
+            PathBuf::from("/nonexistent/project")
+        );
+    }
+}
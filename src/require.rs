@@ -0,0 +1,214 @@
+//! Support for parsing the text output of a composer require dry run
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, log_failed_call, run_composer, text_composer_command, ComposerCommandOptions,
+    Error,
+};
+
+use std::str::from_utf8;
+use tracing::debug;
+
+/// These are options to modify the behaviour of composer require
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerRequireOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// The changes composer would make to the lock file when requiring a package
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PlannedChanges {
+    /// Packages which would be added
+    pub installs: Vec<PlannedPackage>,
+    /// Packages which would change their version
+    pub updates: Vec<PlannedUpdate>,
+    /// Packages which would be removed
+    pub removals: Vec<PlannedPackage>,
+}
+
+impl PlannedChanges {
+    /// would requiring the package change nothing at all
+    pub fn is_empty(&self) -> bool {
+        self.installs.is_empty() && self.updates.is_empty() && self.removals.is_empty()
+    }
+}
+
+/// A package which would be installed or removed
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PlannedPackage {
+    /// Package name
+    pub name: String,
+    /// The version which would be installed or removed
+    pub version: String,
+}
+
+/// A package which would change its version
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PlannedUpdate {
+    /// Package name
+    pub name: String,
+    /// The version currently locked
+    pub from: String,
+    /// The version which would be locked
+    pub to: String,
+}
+
+/// splits `vendor/package (details)` into the name and the details in the
+/// parentheses
+fn split_operation(rest: &str) -> Option<(&str, &str)> {
+    let (name, details) = rest.split_once(" (")?;
+    Some((name.trim(), details.strip_suffix(')')?))
+}
+
+/// parses the operation lines of composer require, update or install, e.g.
+/// `  - Upgrading monolog/monolog (2.9.1 => 3.5.0)`, composer reports the
+/// operations on the lock file and the ones on vendor separately so
+/// operations on a package already seen are skipped
+fn parse_require_output(output: &str) -> PlannedChanges {
+    let mut changes = PlannedChanges::default();
+    let mut seen: Vec<String> = vec![];
+    for line in output.lines() {
+        let Some(operation) = line.trim().strip_prefix("- ") else {
+            continue;
+        };
+        let Some((verb, rest)) = operation.split_once(' ') else {
+            continue;
+        };
+        let Some((name, details)) = split_operation(rest) else {
+            debug!(line, "ignoring composer operation without version");
+            continue;
+        };
+        // the lock file operations use Locking where the vendor operations
+        // use Installing
+        let key = match verb {
+            "Installing" => format!("Locking {}", name),
+            _ => format!("{} {}", verb, name),
+        };
+        if seen.contains(&key) {
+            continue;
+        }
+        match verb {
+            "Locking" | "Installing" => changes.installs.push(PlannedPackage {
+                name: name.to_string(),
+                version: details.to_string(),
+            }),
+            "Removing" => changes.removals.push(PlannedPackage {
+                name: name.to_string(),
+                version: details.to_string(),
+            }),
+            "Upgrading" | "Downgrading" | "Updating" => {
+                let Some((from, to)) = details.split_once(" => ") else {
+                    debug!(line, "ignoring composer update without target version");
+                    continue;
+                };
+                changes.updates.push(PlannedUpdate {
+                    name: name.to_string(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                });
+            }
+            _ => {
+                debug!(line, "ignoring unknown composer operation");
+                continue;
+            }
+        }
+        seen.push(key);
+    }
+    changes
+}
+
+/// previews what requiring the package with the given constraint would
+/// change, this runs composer require with --dry-run and --no-install so
+/// neither composer.json, composer.lock nor vendor are modified
+pub fn require_dry_run(
+    package: &str,
+    constraint: &str,
+    options: &ComposerRequireOptions,
+) -> Result<PlannedChanges, Error> {
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.args(["require", "--dry-run", "--no-install"])
+        .args(plugin_args(&options.command))
+        .arg("--no-interaction");
+    cmd.arg(format!("{}:{}", package, constraint));
+
+    let output = run_composer(cmd, &options.command)?;
+
+    if !output.status.success() {
+        log_failed_call("require", &output);
+        return Err(command_failed(&output));
+    }
+
+    // composer writes the operations to stderr
+    let text = format!(
+        "{}\n{}",
+        from_utf8(&output.stdout)?,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_require_output(&text))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_require_output() {
+        let output = "\
+./composer.json has been updated
+Running composer update monolog/monolog
+Loading composer repositories with package information
+Updating dependencies
+Lock file operations: 1 install, 2 updates, 1 removal
+  - Removing swiftmailer/swiftmailer (v6.3.0)
+  - Locking psr/log (3.0.0)
+  - Upgrading monolog/monolog (2.9.1 => 3.5.0)
+  - Downgrading symfony/polyfill-mbstring (v1.29.0 => v1.28.0)
+Installing dependencies from lock file (including require-dev)
+Package operations: 1 install, 2 updates, 1 removal
+  - Removing swiftmailer/swiftmailer (v6.3.0)
+  - Installing psr/log (3.0.0)
+  - Upgrading monolog/monolog (2.9.1 => 3.5.0)
+  - Downgrading symfony/polyfill-mbstring (v1.29.0 => v1.28.0)
+";
+        assert_eq!(
+            parse_require_output(output),
+            PlannedChanges {
+                installs: vec![PlannedPackage {
+                    name: "psr/log".to_string(),
+                    version: "3.0.0".to_string(),
+                }],
+                updates: vec![
+                    PlannedUpdate {
+                        name: "monolog/monolog".to_string(),
+                        from: "2.9.1".to_string(),
+                        to: "3.5.0".to_string(),
+                    },
+                    PlannedUpdate {
+                        name: "symfony/polyfill-mbstring".to_string(),
+                        from: "v1.29.0".to_string(),
+                        to: "v1.28.0".to_string(),
+                    },
+                ],
+                removals: vec![PlannedPackage {
+                    name: "swiftmailer/swiftmailer".to_string(),
+                    version: "v6.3.0".to_string(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_require_output_nothing_to_do() {
+        let output = "\
+Updating dependencies
+Nothing to modify in lock file
+";
+        assert!(parse_require_output(output).is_empty());
+    }
+}
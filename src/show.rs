@@ -0,0 +1,463 @@
+//! Support for parsing the JSON output of composer show
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, command_line, composer_command, join_pipe_reader, log_failed_call,
+    run_composer, spawn_pipe_reader, spawn_piped, wait_with_timeout, ComposerCommandOptions,
+    ComposerOutdatedData, Error,
+};
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::process::{Child, Command, Output};
+use std::str::from_utf8;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// These are options to modify the behaviour of composer show
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerShowOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// Outer structure for parsing composer-show output
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ComposerShowData {
+    /// All the packages from the lock file, composer calls this field `locked`
+    /// when called with --locked and `installed` otherwise
+    #[serde(alias = "locked")]
+    pub installed: Vec<InstalledPackage>,
+}
+
+/// Inner, per-package structure when parsing composer-show output
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct InstalledPackage {
+    /// Package name
+    pub name: String,
+    /// Package version in use
+    pub version: String,
+    /// Description for the package
+    pub description: String,
+    /// Homepage of the package, if it declares one
+    pub homepage: Option<String>,
+    /// URL of the source of the package, if known
+    pub source: Option<String>,
+    /// Where to get help for the package, if composer reports it, the list
+    /// of all packages [show] gets from composer does not contain this so it
+    /// is always None there, only the details composer shows for a single
+    /// package (`composer show vendor/package -f json`) include it
+    #[serde(default)]
+    pub support: Option<SupportLinks>,
+}
+
+/// Links to get help for a package as declared in its composer.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SupportLinks {
+    /// URL of the issue tracker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issues: Option<String>,
+    /// URL to browse or download the source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// URL of the documentation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+}
+
+/// copies the homepage and support links of the packages in the composer
+/// show output to the packages with the same name in the composer outdated
+/// output, values composer show does not know about are left as they are
+///
+/// The output of [show] only has homepages, support links are only copied
+/// if the show data was built from output which has them, see
+/// [InstalledPackage::support]
+pub fn enrich_with_show(outdated: &mut ComposerOutdatedData, show: &ComposerShowData) {
+    let installed: BTreeMap<&str, &InstalledPackage> = show
+        .installed
+        .iter()
+        .map(|package| (package.name.as_str(), package))
+        .collect();
+    for package in &mut outdated.locked {
+        let Some(installed) = installed.get(package.name.as_str()) else {
+            continue;
+        };
+        if installed.homepage.is_some() {
+            package.homepage = installed.homepage.clone();
+        }
+        if installed.support.is_some() {
+            package.support = installed.support.clone();
+        }
+    }
+}
+
+/// creates the composer show command from the options
+fn show_command(options: &ComposerShowOptions) -> Result<Command, Error> {
+    let mut cmd = composer_command(&options.command)?;
+
+    cmd.args(["show", "-f", "json"])
+        .args(plugin_args(&options.command))
+        .arg("--locked");
+
+    Ok(cmd)
+}
+
+/// main entry point for the composer-show call
+pub fn show(options: &ComposerShowOptions) -> Result<ComposerShowData, Error> {
+    let output = run_composer(show_command(options)?, &options.command)?;
+
+    if !output.status.success() {
+        log_failed_call("show", &output);
+        return Err(command_failed(&output));
+    }
+
+    let json_str = from_utf8(&output.stdout)?;
+    let data: ComposerShowData = serde_json::from_str(json_str)?;
+    Ok(data)
+}
+
+/// visits the top-level object of the composer show output and passes the
+/// packages in the `installed` (or `locked`) field to the callback
+struct ShowVisitor<'a> {
+    /// called with every package
+    callback: &'a mut dyn FnMut(InstalledPackage),
+}
+
+impl<'de> serde::de::Visitor<'de> for ShowVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("the composer show output")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut found = false;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "installed" || key == "locked" {
+                map.next_value_seed(PackagesSeed {
+                    callback: &mut *self.callback,
+                })?;
+                found = true;
+            } else {
+                map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        }
+        if !found {
+            return Err(serde::de::Error::missing_field("installed"));
+        }
+        Ok(())
+    }
+}
+
+/// deserializes the list of packages one at a time, passing each to the
+/// callback instead of collecting them
+struct PackagesSeed<'a> {
+    /// called with every package
+    callback: &'a mut dyn FnMut(InstalledPackage),
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for PackagesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for PackagesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a list of packages")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        while let Some(package) = seq.next_element::<InstalledPackage>()? {
+            (self.callback)(package);
+        }
+        Ok(())
+    }
+}
+
+/// parses the JSON output of composer show from the reader, calling the
+/// callback with every package as soon as it is read instead of collecting
+/// them, so only one package is kept in memory at a time, the packages
+/// before a parse error have already been passed to the callback when the
+/// error is returned
+pub fn parse_show_stream<R, F>(reader: R, mut callback: F) -> Result<(), Error>
+where
+    R: Read,
+    F: FnMut(InstalledPackage),
+{
+    let mut deserializer = serde_json::Deserializer::from_reader(std::io::BufReader::new(reader));
+    serde::Deserializer::deserialize_map(
+        &mut deserializer,
+        ShowVisitor {
+            callback: &mut callback,
+        },
+    )?;
+    deserializer.end()?;
+    Ok(())
+}
+
+/// kills the child once the timeout after it was started has passed unless
+/// the returned sender is dropped before, this enforces the timeout while
+/// the output of the child is still being read, the thread returns whether
+/// it killed the child
+fn spawn_watchdog(
+    child: Arc<Mutex<Child>>,
+    timeout: Option<Duration>,
+    started: Instant,
+) -> (Sender<()>, JoinHandle<bool>) {
+    let (sender, receiver) = channel::<()>();
+    let handle = std::thread::spawn(move || {
+        let Some(timeout) = timeout else {
+            return false;
+        };
+        let remaining = (started + timeout).saturating_duration_since(Instant::now());
+        if receiver.recv_timeout(remaining) != Err(RecvTimeoutError::Timeout) {
+            return false;
+        }
+        warn!(
+            timeout_secs = timeout.as_secs_f64(),
+            "composer did not finish within the timeout, killing it"
+        );
+        let mut child = child.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = child.kill() {
+            debug!(error = %err, "could not kill composer");
+        }
+        true
+    });
+    (sender, handle)
+}
+
+/// streaming variant of [show] for very large projects, the output of
+/// composer is parsed while it is read and every package is passed to the
+/// callback as soon as it is complete (see [parse_show_stream]) instead of
+/// collecting the output and all packages in memory first
+///
+/// The trade-off is that the number of packages is not known up front and
+/// that the callback may already have been called for some packages when
+/// composer fails or its output turns out to be invalid, composer is not
+/// retried for the same reason, if the output can not be parsed the parse
+/// error is returned even if composer failed too since composer usually dies
+/// from the closed pipe once the parsing stopped
+pub fn show_stream<F>(options: &ComposerShowOptions, callback: F) -> Result<(), Error>
+where
+    F: FnMut(InstalledPackage),
+{
+    let mut cmd = show_command(options)?;
+    debug!(command_line = ?command_line(&cmd), "running composer");
+
+    let started = Instant::now();
+    let mut child = spawn_piped(&mut cmd)?;
+    let stderr_reader = spawn_pipe_reader(child.stderr.take());
+    let stdout = child.stdout.take();
+    let child = Arc::new(Mutex::new(child));
+    let (parsing, watchdog) = spawn_watchdog(Arc::clone(&child), options.command.timeout, started);
+
+    // the pipe is closed when stdout is dropped at the end of the parsing so
+    // composer can not block on a full pipe if we stop reading early
+    let parsed = match stdout {
+        Some(stdout) => parse_show_stream(stdout, callback),
+        None => Ok(()),
+    };
+    drop(parsing);
+    let killed = watchdog.join().unwrap_or(false);
+
+    let mut child = child.lock().unwrap_or_else(PoisonError::into_inner);
+    if let (true, Some(timeout)) = (killed, options.command.timeout) {
+        // reap the killed process so it does not linger as a zombie
+        child.wait()?;
+        return Err(Error::Timeout(timeout));
+    }
+
+    let output = Output {
+        status: wait_with_timeout(&mut child, options.command.timeout, started)?,
+        stdout: vec![],
+        stderr: join_pipe_reader(stderr_reader)?,
+    };
+
+    if !output.status.success() {
+        log_failed_call("show", &output);
+    }
+    parsed?;
+    if !output.status.success() {
+        return Err(command_failed(&output));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_show_stream() -> Result<(), Error> {
+        let json = r#"{
+            "installed": [
+                {
+                    "name": "monolog/monolog",
+                    "version": "3.5.0",
+                    "description": "Sends your logs to files, sockets, inboxes, databases and various web services",
+                    "homepage": "https://github.com/Seldaek/monolog",
+                    "source": null,
+                    "abandoned": false
+                },
+                {
+                    "name": "psr/log",
+                    "version": "3.0.0",
+                    "description": "Common interface for logging libraries",
+                    "homepage": null,
+                    "source": null
+                }
+            ],
+            "extra": {"ignored": [1, 2, 3]}
+        }"#;
+        let mut names = vec![];
+        parse_show_stream(json.as_bytes(), |package| names.push(package.name))?;
+        assert_eq!(names, vec!["monolog/monolog", "psr/log"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_show_stream_invalid() {
+        let mut names = vec![];
+        let result = parse_show_stream(
+            r#"{"locked": [{"name": "psr/log", "version": "3.0.0", "description": ""}, {"name": 1}]}"#
+                .as_bytes(),
+            |package| names.push(package.name),
+        );
+        assert!(matches!(result, Err(Error::SerdeJsonError(_))));
+        assert_eq!(names, vec!["psr/log"]);
+        assert!(matches!(
+            parse_show_stream(r#"{"packages": []}"#.as_bytes(), |_| {}),
+            Err(Error::SerdeJsonError(_))
+        ));
+        assert!(matches!(
+            parse_show_stream(r#"{"locked": []} trailing"#.as_bytes(), |_| {}),
+            Err(Error::SerdeJsonError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_show_output() -> Result<(), Error> {
+        let json = r#"{
+            "locked": [
+                {
+                    "name": "monolog/monolog",
+                    "direct-dependency": true,
+                    "homepage": "https://github.com/Seldaek/monolog",
+                    "source": "https://github.com/Seldaek/monolog/tree/3.5.0",
+                    "version": "3.5.0",
+                    "description": "Sends your logs to files, sockets, inboxes, databases and various web services",
+                    "abandoned": false
+                },
+                {
+                    "name": "psr/log",
+                    "direct-dependency": false,
+                    "homepage": null,
+                    "source": null,
+                    "version": "3.0.0",
+                    "description": "Common interface for logging libraries",
+                    "abandoned": false
+                }
+            ]
+        }"#;
+        let data: ComposerShowData = serde_json::from_str(json)?;
+        assert_eq!(data.installed.len(), 2);
+        assert_eq!(data.installed[0].name, "monolog/monolog");
+        assert_eq!(
+            data.installed[0].homepage.as_deref(),
+            Some("https://github.com/Seldaek/monolog")
+        );
+        assert_eq!(data.installed[1].version, "3.0.0");
+        assert_eq!(data.installed[1].source, None);
+        assert_eq!(data.installed[1].support, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enrich_with_show() -> Result<(), Error> {
+        let show: ComposerShowData = serde_json::from_str(
+            r#"{
+                "installed": [
+                    {
+                        "name": "monolog/monolog",
+                        "version": "3.4.0",
+                        "description": "Sends your logs to files, sockets, inboxes, databases and various web services",
+                        "homepage": "https://github.com/Seldaek/monolog",
+                        "source": "https://github.com/Seldaek/monolog/tree/3.4.0",
+                        "support": {
+                            "issues": "https://github.com/Seldaek/monolog/issues",
+                            "source": "https://github.com/Seldaek/monolog/tree/3.4.0"
+                        }
+                    },
+                    {
+                        "name": "psr/log",
+                        "version": "3.0.0",
+                        "description": "Common interface for logging libraries",
+                        "homepage": null,
+                        "source": null
+                    }
+                ]
+            }"#,
+        )?;
+        let mut outdated: ComposerOutdatedData = serde_json::from_str(
+            r#"{
+                "locked": [
+                    {
+                        "name": "monolog/monolog",
+                        "version": "3.4.0",
+                        "latest": "3.5.0",
+                        "latest-status": "semver-safe-update",
+                        "description": ""
+                    },
+                    {
+                        "name": "psr/log",
+                        "homepage": "https://github.com/php-fig/log",
+                        "version": "3.0.0",
+                        "latest": "3.0.0",
+                        "latest-status": "up-to-date",
+                        "description": ""
+                    }
+                ]
+            }"#,
+        )?;
+        enrich_with_show(&mut outdated, &show);
+        assert_eq!(
+            outdated.locked[0].homepage.as_deref(),
+            Some("https://github.com/Seldaek/monolog")
+        );
+        assert_eq!(
+            outdated.locked[0].support,
+            Some(SupportLinks {
+                issues: Some("https://github.com/Seldaek/monolog/issues".to_string()),
+                source: Some("https://github.com/Seldaek/monolog/tree/3.4.0".to_string()),
+                docs: None,
+            })
+        );
+        assert_eq!(
+            outdated.locked[1].homepage.as_deref(),
+            Some("https://github.com/php-fig/log")
+        );
+        assert_eq!(outdated.locked[1].support, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+//! Support for parsing the text output of composer status
+
+use crate::plugins::plugin_args;
+use crate::{
+    command_failed, log_failed_call, run_composer, text_composer_command, ComposerCommandOptions,
+    Error,
+};
+
+use std::str::from_utf8;
+use tracing::debug;
+
+/// These are options to modify the behaviour of composer status
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerStatusOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// Bit in the exit code of composer status signalling local changes
+const EXIT_CODE_LOCAL_CHANGES: i32 = 1;
+/// Bit in the exit code of composer status signalling unpushed changes
+const EXIT_CODE_UNPUSHED_CHANGES: i32 = 2;
+/// Bit in the exit code of composer status signalling version changes
+const EXIT_CODE_VERSION_CHANGES: i32 = 4;
+
+/// The result of checking the installed packages for local modifications
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StatusReport {
+    /// Do any packages in vendor have local changes
+    pub has_local_changes: bool,
+    /// Do any packages installed from source have unpushed changes
+    pub has_unpushed_changes: bool,
+    /// Do any packages installed from source have a different version checked
+    /// out than the one locked
+    pub has_version_changes: bool,
+    /// The packages composer reported, composer writes the headings telling
+    /// which kind of change a package has to stderr so packages with unpushed
+    /// changes or version changes are included here too
+    pub modified_packages: Vec<ModifiedPackage>,
+}
+
+/// A package composer status reported as modified
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModifiedPackage {
+    /// Package name, composer only reports the install path so this is
+    /// derived from its last two components and may be wrong for packages
+    /// installed to custom paths
+    pub name: String,
+    /// The path the package is installed at
+    pub path: String,
+    /// The changes, e.g. `M src/Foo.php` as reported by the VCS
+    pub changes: Vec<String>,
+}
+
+/// derives the package name from the install path of a package, e.g.
+/// `/project/vendor/symfony/console` becomes `symfony/console`
+fn package_name_from_path(path: &str) -> String {
+    let mut components = path.rsplit(['/', '\\']).filter(|c| !c.is_empty());
+    match (components.next(), components.next()) {
+        (Some(name), Some(vendor)) => format!("{}/{}", vendor, name),
+        (Some(name), None) => name.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// parses the verbose text output of composer status, every package is
+/// reported as its install path followed by a colon and the changes indented
+/// on the following lines
+fn parse_status_output(exit_code: i32, output: &str) -> StatusReport {
+    let mut modified_packages: Vec<ModifiedPackage> = vec![];
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            match modified_packages.last_mut() {
+                Some(package) => package.changes.push(line.trim().to_string()),
+                None => debug!(line, "composer status change outside of a package"),
+            }
+            continue;
+        }
+        // the preamble lines are written to stderr by current composer
+        // versions but might end up in the output anyway
+        if line.starts_with("You have ")
+            || line.starts_with("No local changes")
+            || line.starts_with("Use --verbose")
+        {
+            continue;
+        }
+        let path = line.trim().trim_end_matches(':');
+        modified_packages.push(ModifiedPackage {
+            name: package_name_from_path(path),
+            path: path.to_string(),
+            changes: vec![],
+        });
+    }
+    StatusReport {
+        has_local_changes: exit_code & EXIT_CODE_LOCAL_CHANGES != 0,
+        has_unpushed_changes: exit_code & EXIT_CODE_UNPUSHED_CHANGES != 0,
+        has_version_changes: exit_code & EXIT_CODE_VERSION_CHANGES != 0,
+        modified_packages,
+    }
+}
+
+/// main entry point for the composer-status call
+pub fn status(options: &ComposerStatusOptions) -> Result<StatusReport, Error> {
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.args(["status", "-v"])
+        .args(plugin_args(&options.command));
+
+    let output = run_composer(cmd, &options.command)?;
+
+    // composer reports the kinds of changes found as bits in the exit code
+    let exit_code = match output.status.code() {
+        Some(code) if (0..=7).contains(&code) => code,
+        _ => {
+            log_failed_call("status", &output);
+            return Err(command_failed(&output));
+        }
+    };
+
+    Ok(parse_status_output(exit_code, from_utf8(&output.stdout)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_status_output() {
+        let output = "\
+You have changes in the following dependencies:
+/srv/project/vendor/symfony/console:
+    M Command/Command.php
+    ?? debug.log
+/srv/project/vendor/monolog/monolog:
+     M src/Monolog/Logger.php
+";
+        assert_eq!(
+            parse_status_output(1, output),
+            StatusReport {
+                has_local_changes: true,
+                has_unpushed_changes: false,
+                has_version_changes: false,
+                modified_packages: vec![
+                    ModifiedPackage {
+                        name: "symfony/console".to_string(),
+                        path: "/srv/project/vendor/symfony/console".to_string(),
+                        changes: vec![
+                            "M Command/Command.php".to_string(),
+                            "?? debug.log".to_string()
+                        ],
+                    },
+                    ModifiedPackage {
+                        name: "monolog/monolog".to_string(),
+                        path: "/srv/project/vendor/monolog/monolog".to_string(),
+                        changes: vec!["M src/Monolog/Logger.php".to_string()],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_status_output_without_changes() {
+        assert_eq!(
+            parse_status_output(0, ""),
+            StatusReport {
+                has_local_changes: false,
+                has_unpushed_changes: false,
+                has_version_changes: false,
+                modified_packages: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_package_name_from_path() {
+        assert_eq!(
+            package_name_from_path("vendor/symfony/console"),
+            "symfony/console"
+        );
+        assert_eq!(
+            package_name_from_path("C:\\project\\vendor\\symfony\\console\\"),
+            "symfony/console"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Strict parsing of the composer outdated output which rejects fields this
+//! crate does not know about, to notice changes in the output format early
+
+use crate::{parse_outdated_json, unexpected_format, ComposerOutdatedData, Error};
+
+use serde::de::IgnoredAny;
+
+/// the known shape of the composer outdated output
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictOutdatedData {
+    /// the packages
+    #[serde(rename = "locked")]
+    _locked: Vec<StrictPackageStatus>,
+}
+
+/// all the fields composer outdated reports for a package, including the
+/// ones which are not part of [PackageStatus](crate::PackageStatus), and the
+/// ones we add ourselves
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictPackageStatus {
+    /// the package name
+    #[serde(rename = "name")]
+    _name: IgnoredAny,
+    /// is the package required by the root package
+    #[serde(rename = "direct-dependency", default)]
+    _direct_dependency: IgnoredAny,
+    /// the homepage of the package
+    #[serde(rename = "homepage", default)]
+    _homepage: IgnoredAny,
+    /// the source of the package
+    #[serde(rename = "source", default)]
+    _source: IgnoredAny,
+    /// the path the package is installed at
+    #[serde(rename = "path", default)]
+    _path: IgnoredAny,
+    /// the version in use
+    #[serde(rename = "version")]
+    _version: IgnoredAny,
+    /// how long ago the version in use was released
+    #[serde(rename = "release-age", default)]
+    _release_age: IgnoredAny,
+    /// when the version in use was released
+    #[serde(rename = "release-date", default)]
+    _release_date: IgnoredAny,
+    /// the latest version
+    #[serde(rename = "latest")]
+    _latest: IgnoredAny,
+    /// the update requirement
+    #[serde(rename = "latest-status")]
+    _latest_status: IgnoredAny,
+    /// when the latest version was released
+    #[serde(rename = "latest-release-date", default)]
+    _latest_release_date: IgnoredAny,
+    /// the description of the package
+    #[serde(rename = "description")]
+    _description: IgnoredAny,
+    /// is the package abandoned, or the suggested replacement
+    #[serde(rename = "abandoned", default)]
+    _abandoned: IgnoredAny,
+    /// further notes
+    #[serde(rename = "warning", default)]
+    _warning: IgnoredAny,
+    /// added by us from the lock file
+    #[serde(rename = "is_dev", default)]
+    _is_dev: IgnoredAny,
+    /// added by us from composer show
+    #[serde(rename = "support", default)]
+    _support: IgnoredAny,
+}
+
+/// parses the JSON output of composer outdated like [parse_outdated_json] but
+/// returns an error if composer reports any field which is not known to this
+/// crate, e.g. to notice format changes of a new composer version in CI
+///
+/// ```
+/// let json = r#"{"locked": [{"name": "psr/log", "version": "3.0.0",
+///     "latest": "3.0.0", "latest-status": "up-to-date", "description": "",
+///     "some-new-field": true}]}"#;
+/// assert!(composer_parser::parse_outdated_json(json).is_ok());
+/// assert!(composer_parser::parse_outdated_json_strict(json).is_err());
+/// ```
+pub fn parse_outdated_json_strict(json: &str) -> Result<ComposerOutdatedData, Error> {
+    let _: StrictOutdatedData = serde_json::from_str(json)
+        .map_err(|err| unexpected_format(json.as_bytes(), "locked", err))?;
+    parse_outdated_json(json)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_outdated_json_strict() -> Result<(), Error> {
+        let json = r#"{
+            "locked": [
+                {
+                    "name": "monolog/monolog",
+                    "direct-dependency": true,
+                    "homepage": "https://github.com/Seldaek/monolog",
+                    "source": "https://github.com/Seldaek/monolog/tree/3.4.0",
+                    "version": "3.4.0",
+                    "release-age": "1 year old",
+                    "release-date": "2023-06-21T08:46:11+00:00",
+                    "latest": "3.5.0",
+                    "latest-status": "semver-safe-update",
+                    "latest-release-date": "2023-10-27T15:32:31+00:00",
+                    "description": "Sends your logs to files, sockets, inboxes, databases and various web services",
+                    "abandoned": false
+                }
+            ]
+        }"#;
+        let data = parse_outdated_json_strict(json)?;
+        assert_eq!(data.locked.len(), 1);
+        assert_eq!(data.locked[0].name, "monolog/monolog");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_outdated_json_strict_unknown_field() {
+        let json = r#"{
+            "locked": [
+                {
+                    "name": "psr/log",
+                    "version": "3.0.0",
+                    "latest": "3.0.0",
+                    "latest-status": "up-to-date",
+                    "description": "",
+                    "funding-url": "https://example.com"
+                }
+            ]
+        }"#;
+        let result = parse_outdated_json_strict(json);
+        assert!(
+            matches!(&result, Err(Error::SerdeJsonError(err)) if err.to_string().contains("funding-url")),
+            "expected an error about the unknown field, got {:?}",
+            result
+        );
+        assert!(parse_outdated_json(json).is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+//! Support for parsing the text output of composer validate
+
+use crate::plugins::plugin_args;
+use crate::{log_failed_call, run_composer, text_composer_command, ComposerCommandOptions, Error};
+
+use std::str::from_utf8;
+use tracing::{debug, warn};
+
+/// These are options to modify the behaviour of composer validate
+#[derive(Debug, Default, clap::Parser, serde::Deserialize)]
+#[serde(default)]
+pub struct ComposerValidateOptions {
+    /// Options controlling how composer is invoked
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub command: ComposerCommandOptions,
+}
+
+/// The result of validating composer.json and composer.lock
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ValidationReport {
+    /// Did composer consider the project valid, this is based on the exit
+    /// code
+    pub is_valid: bool,
+    /// The warnings composer reported
+    pub warnings: Vec<String>,
+    /// The errors composer reported, including lock file errors
+    pub errors: Vec<String>,
+    /// Is the lock file up to date with composer.json
+    pub lock_up_to_date: bool,
+}
+
+/// The kind of messages in a section of the composer validate output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValidateSection {
+    /// a section containing errors, e.g. `# Errors` or `# Lock file errors`
+    Errors,
+    /// a section containing warnings, e.g. `# General warnings`
+    Warnings,
+}
+
+/// parses the text output of composer validate into a report, composer uses
+/// headings like `# Errors` or `# General warnings` followed by a list of
+/// messages each starting with `- `
+fn parse_validate_output(is_valid: bool, output: &str) -> ValidationReport {
+    let mut report = ValidationReport {
+        is_valid,
+        warnings: vec![],
+        errors: vec![],
+        lock_up_to_date: true,
+    };
+    let mut section = None;
+    for line in output.lines() {
+        let line = line.trim_end();
+        if let Some(heading) = line.strip_prefix("# ") {
+            let heading = heading.to_lowercase();
+            section = if heading.contains("error") {
+                Some(ValidateSection::Errors)
+            } else if heading.contains("warning") {
+                Some(ValidateSection::Warnings)
+            } else {
+                debug!(line, "unrecognized composer validate heading");
+                None
+            };
+        } else if let Some(message) = line.strip_prefix("- ") {
+            if message.contains("lock file is not up to date") {
+                report.lock_up_to_date = false;
+            }
+            match section {
+                Some(ValidateSection::Errors) => report.errors.push(message.to_string()),
+                Some(ValidateSection::Warnings) => report.warnings.push(message.to_string()),
+                None => debug!(line, "composer validate message outside of a section"),
+            }
+        } else if line.contains("lock file is not up to date") {
+            // older composer versions report this outside of any section
+            report.lock_up_to_date = false;
+            report.warnings.push(line.to_string());
+        }
+    }
+    report
+}
+
+/// Exit code of composer validate signalling composer.json could not be read
+const EXIT_CODE_UNREADABLE: i32 = 3;
+
+/// main entry point for the composer-validate call, an unsuccessful exit code
+/// of composer is reported as an invalid project, not as an error, unless
+/// composer.json could not be read at all which is reported as
+/// [Error::Composer]
+pub fn validate(options: &ComposerValidateOptions) -> Result<ValidationReport, Error> {
+    let mut cmd = text_composer_command(&options.command)?;
+
+    cmd.arg("validate")
+        .args(plugin_args(&options.command))
+        .arg("--no-check-publish");
+
+    let output = run_composer(cmd, &options.command)?;
+
+    if output.status.code() == Some(EXIT_CODE_UNREADABLE) {
+        log_failed_call("validate", &output);
+        return Err(Error::Composer(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    if !output.status.success() {
+        warn!(
+            command = "validate",
+            exit_code = output.status.code(),
+            "composer did not return with a successful exit code"
+        );
+    }
+
+    // composer writes most of its validation messages to stderr
+    let text = format!(
+        "{}\n{}",
+        from_utf8(&output.stdout)?,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_validate_output(output.status.success(), &text))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_validate_output_valid() {
+        assert_eq!(
+            parse_validate_output(true, "./composer.json is valid\n"),
+            ValidationReport {
+                is_valid: true,
+                warnings: vec![],
+                errors: vec![],
+                lock_up_to_date: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_validate_output_invalid() {
+        let output = "\
+./composer.json is invalid, the following errors/warnings were found:
+# Errors
+- require.monolog/monolog : invalid version constraint (Could not parse version constraint ^^3.0: Invalid version string \"^^3.0\")
+# General warnings
+- No license specified, it is recommended to do so. For closed-source software you may use \"proprietary\" as license.
+# Lock file errors
+- The lock file is not up to date with the latest changes in composer.json, it is recommended that you run `composer update` or `composer update <package name>`.
+";
+        let report = parse_validate_output(false, output);
+        assert!(!report.is_valid);
+        assert!(!report.lock_up_to_date);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors[0].starts_with("require.monolog/monolog"));
+        assert!(report.errors[1].starts_with("The lock file is not up to date"));
+        assert_eq!(
+            report.warnings,
+            vec!["No license specified, it is recommended to do so. For closed-source software you may use \"proprietary\" as license."]
+        );
+    }
+
+    #[test]
+    fn test_parse_validate_output_old_lock_warning() {
+        let output = "\
+./composer.json is valid
+The lock file is not up to date with the latest changes in composer.json, it is recommended that you run `composer update` or `composer update <package name>`.
+";
+        let report = parse_validate_output(true, output);
+        assert!(!report.lock_up_to_date);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.errors.is_empty());
+    }
+}
@@ -0,0 +1,444 @@
+//! Interpretation of the version strings reported by composer as semantic
+//! versions
+
+use crate::{
+    command_failed, is_branch_version, log_failed_call, CommandRunner, ComposerCommandOptions,
+    ComposerOutdatedData, Error, PackageStatus, SystemRunner,
+};
+
+use std::process::Output;
+use std::str::from_utf8;
+
+/// The semver level of the update from the version in use to the latest
+/// version, see [PackageStatus::update_class]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdateClass {
+    /// There is no newer version or the versions can not be compared
+    None,
+    /// Only the patch version (or the pre-release) differs
+    Patch,
+    /// The minor version differs
+    Minor,
+    /// The major version differs
+    Major,
+}
+
+impl std::fmt::Display for UpdateClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateClass::None => {
+                write!(f, "none")
+            }
+            UpdateClass::Patch => {
+                write!(f, "patch")
+            }
+            UpdateClass::Minor => {
+                write!(f, "minor")
+            }
+            UpdateClass::Major => {
+                write!(f, "major")
+            }
+        }
+    }
+}
+
+/// The kind of version bump between the version in use and the latest version
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionBump {
+    /// Only the patch version (or the pre-release) differs
+    Patch,
+    /// The minor version differs
+    Minor,
+    /// The major version differs
+    Major,
+    /// The version in use or the latest version is a branch like
+    /// `dev-main` or `9999999-dev`, these can not be compared
+    Branch,
+}
+
+impl std::fmt::Display for VersionBump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionBump::Patch => {
+                write!(f, "patch")
+            }
+            VersionBump::Minor => {
+                write!(f, "minor")
+            }
+            VersionBump::Major => {
+                write!(f, "major")
+            }
+            VersionBump::Branch => {
+                write!(f, "branch")
+            }
+        }
+    }
+}
+
+/// How far the version in use is behind the latest version in major versions
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Staleness {
+    /// The version in use has the same major version as the latest version,
+    /// or the versions can not be compared
+    Current,
+    /// The latest version is the given number of major versions ahead
+    Behind(u64),
+}
+
+impl std::fmt::Display for Staleness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Staleness::Current => {
+                write!(f, "current")
+            }
+            Staleness::Behind(1) => {
+                write!(f, "1 major version behind")
+            }
+            Staleness::Behind(majors) => {
+                write!(f, "{majors} major versions behind")
+            }
+        }
+    }
+}
+
+/// parses a version as composer reports it into a semantic version
+///
+/// a leading `v` is stripped and missing minor or patch components are
+/// filled in with zero (composer allows e.g. `1.2`), branch versions like
+/// `dev-main` or `2.x-dev` are not semantic versions and return an error
+pub fn parse_composer_version(version: &str) -> Result<semver::Version, Error> {
+    let version = version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .unwrap_or(version);
+    let core_end = version.find(['-', '+']).unwrap_or(version.len());
+    let (core, rest) = version.split_at(core_end);
+    let padding = match core.matches('.').count() {
+        0 => ".0.0",
+        1 => ".0",
+        _ => "",
+    };
+    Ok(semver::Version::parse(&format!("{core}{padding}{rest}"))?)
+}
+
+/// The version of composer itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposerVersion {
+    /// The version reported by composer --version
+    pub version: semver::Version,
+}
+
+impl ComposerVersion {
+    /// The oldest composer version whose output this crate can parse
+    pub const MINIMUM_SUPPORTED: semver::Version = semver::Version::new(2, 0, 0);
+
+    /// is this composer version recent enough for this crate
+    pub fn is_supported(&self) -> bool {
+        self.version >= Self::MINIMUM_SUPPORTED
+    }
+}
+
+impl std::fmt::Display for ComposerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.version)
+    }
+}
+
+/// the arguments passed to composer to query its version
+pub(crate) const VERSION_ARGS: [&str; 2] = ["--version", "--no-ansi"];
+
+/// parses the output of composer --version, e.g.
+/// `Composer version 2.7.1 2024-02-09 15:26:28`, newer versions also print
+/// the PHP version on the following lines
+fn parse_composer_version_output(output: &str) -> Result<ComposerVersion, Error> {
+    let version = output
+        .lines()
+        .find_map(|line| {
+            let rest = line.trim().strip_prefix("Composer ")?;
+            let rest = rest.strip_prefix("version ").unwrap_or(rest);
+            rest.split_whitespace().next()
+        })
+        .ok_or_else(|| Error::ParseComposerVersion(output.to_string()))?;
+    Ok(ComposerVersion {
+        version: parse_composer_version(version)?,
+    })
+}
+
+/// interprets the output of the composer --version call, shared by the sync
+/// and async variants
+pub(crate) fn composer_version_output(output: &Output) -> Result<ComposerVersion, Error> {
+    if !output.status.success() {
+        log_failed_call("--version", output);
+        return Err(command_failed(output));
+    }
+    parse_composer_version_output(from_utf8(&output.stdout)?)
+}
+
+/// returns an error if the composer version is too old for this crate
+pub(crate) fn ensure_supported(version: ComposerVersion) -> Result<(), Error> {
+    if version.is_supported() {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedComposerVersion {
+            found: version.version,
+            minimum: ComposerVersion::MINIMUM_SUPPORTED,
+        })
+    }
+}
+
+/// variant of [composer_version] which runs composer using the given runner
+pub fn composer_version_with<R: CommandRunner>(runner: &R) -> Result<ComposerVersion, Error> {
+    let args: Vec<String> = VERSION_ARGS.into_iter().map(String::from).collect();
+    let output = runner.run(&args)?;
+    composer_version_output(&output)
+}
+
+/// queries the version of composer, e.g. to gate behaviour which differs
+/// between composer versions
+pub fn composer_version(options: &ComposerCommandOptions) -> Result<ComposerVersion, Error> {
+    composer_version_with(&SystemRunner::new(options))
+}
+
+impl PackageStatus {
+    /// the version in use parsed as a semantic version
+    pub fn parsed_version(&self) -> Result<semver::Version, Error> {
+        parse_composer_version(&self.version)
+    }
+
+    /// the latest version available parsed as a semantic version
+    pub fn parsed_latest(&self) -> Result<semver::Version, Error> {
+        parse_composer_version(&self.latest)
+    }
+
+    /// the kind of version bump needed to get from the version in use to the
+    /// latest version, [VersionBump::Branch] if either is a branch, returns
+    /// None if either is not a semantic version otherwise or the latest
+    /// version is not newer than the one in use
+    pub fn version_delta(&self) -> Option<VersionBump> {
+        if self.is_dev_version() || is_branch_version(&self.latest) {
+            return Some(VersionBump::Branch);
+        }
+        let version = self.parsed_version().ok()?;
+        let latest = self.parsed_latest().ok()?;
+        if latest <= version {
+            None
+        } else if latest.major != version.major {
+            Some(VersionBump::Major)
+        } else if latest.minor != version.minor {
+            Some(VersionBump::Minor)
+        } else {
+            Some(VersionBump::Patch)
+        }
+    }
+
+    /// classifies the update to the latest version by the semver component
+    /// which changes, based only on the versions and independent of
+    /// composer's [latest_status](PackageStatus::latest_status)
+    ///
+    /// The two can disagree: composer reports a
+    /// [SemverSafeUpdate](crate::UpdateRequirement::SemverSafeUpdate) for both patch
+    /// and minor updates and by caret semantics treats a minor update of a
+    /// 0.x version like 0.2.0 to 0.3.0 as
+    /// [UpdatePossible](crate::UpdateRequirement::UpdatePossible) while this
+    /// reports [UpdateClass::Minor] for it, a change of only the pre-release
+    /// like 1.0.0-beta1 to 1.0.0 is a [UpdateClass::Patch], branches and
+    /// versions which can not be parsed are [UpdateClass::None], see
+    /// [version_delta](PackageStatus::version_delta) to tell those apart
+    pub fn update_class(&self) -> UpdateClass {
+        match self.version_delta() {
+            Some(VersionBump::Patch) => UpdateClass::Patch,
+            Some(VersionBump::Minor) => UpdateClass::Minor,
+            Some(VersionBump::Major) => UpdateClass::Major,
+            Some(VersionBump::Branch) | None => UpdateClass::None,
+        }
+    }
+
+    /// the number of major versions the latest version is ahead of the
+    /// version in use, None if either is not a semantic version
+    pub fn major_versions_behind(&self) -> Option<u64> {
+        let version = self.parsed_version().ok()?;
+        let latest = self.parsed_latest().ok()?;
+        Some(latest.major.saturating_sub(version.major))
+    }
+
+    /// a rough staleness of the version in use for reporting, versions which
+    /// can not be parsed are reported as [Staleness::Current]
+    pub fn staleness(&self) -> Staleness {
+        match self.major_versions_behind() {
+            Some(0) | None => Staleness::Current,
+            Some(majors) => Staleness::Behind(majors),
+        }
+    }
+}
+
+/// returns an error for the first package whose version in use or latest
+/// version can not be parsed by [parse_composer_version]
+pub(crate) fn ensure_semver(data: &ComposerOutdatedData) -> Result<(), Error> {
+    for package in &data.locked {
+        for version in [&package.version, &package.latest] {
+            if parse_composer_version(version).is_err() {
+                return Err(Error::NonSemverVersion {
+                    package: package.name.to_string(),
+                    version: version.to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::package_name::test::test_package;
+    use crate::UpdateRequirement;
+    use pretty_assertions::assert_eq;
+
+    /// creates a package status with the given versions
+    fn package(version: &str, latest: &str) -> PackageStatus {
+        test_package(
+            "vendor/package",
+            version,
+            latest,
+            UpdateRequirement::UpdatePossible,
+        )
+    }
+
+    #[test]
+    fn test_parse_composer_version() -> Result<(), Error> {
+        assert_eq!(
+            parse_composer_version("v1.2.3")?,
+            semver::Version::new(1, 2, 3)
+        );
+        assert_eq!(
+            parse_composer_version("1.2")?,
+            semver::Version::new(1, 2, 0)
+        );
+        assert_eq!(parse_composer_version("2")?, semver::Version::new(2, 0, 0));
+        assert_eq!(
+            parse_composer_version("1.0-beta1")?,
+            semver::Version::parse("1.0.0-beta1")?
+        );
+        assert!(parse_composer_version("dev-main").is_err());
+        assert!(parse_composer_version("2.x-dev").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_composer_version_output() -> Result<(), Error> {
+        let output = "Composer version 2.8.1 2024-10-04 11:31:01
+PHP version 8.3.12 (/usr/bin/php8.3)
+Run the \"diagnose\" command to get more detailed diagnostics output.
+";
+        let version = parse_composer_version_output(output)?;
+        assert_eq!(version.version, semver::Version::new(2, 8, 1));
+        assert!(version.is_supported());
+        let version = parse_composer_version_output("Composer 1.10.26 2022-04-13 16:39:56")?;
+        assert_eq!(version.version, semver::Version::new(1, 10, 26));
+        assert!(!version.is_supported());
+        assert!(matches!(
+            parse_composer_version_output("PHP version 8.3.12"),
+            Err(Error::ParseComposerVersion(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure_semver() {
+        let data = |version: &str, latest: &str| ComposerOutdatedData {
+            locked: vec![package("1.0.0", "1.0.1"), package(version, latest)],
+        };
+        assert!(ensure_semver(&data("v1.2", "2.0.0-beta1")).is_ok());
+        assert!(ensure_semver(&ComposerOutdatedData { locked: vec![] }).is_ok());
+        assert!(matches!(
+            ensure_semver(&data("dev-main", "1.0.0")),
+            Err(Error::NonSemverVersion { package, version })
+                if package == "vendor/package" && version == "dev-main"
+        ));
+        assert!(matches!(
+            ensure_semver(&data("1.0.0", "2.x-dev")),
+            Err(Error::NonSemverVersion { version, .. }) if version == "2.x-dev"
+        ));
+    }
+
+    #[test]
+    fn test_major_versions_behind() {
+        assert_eq!(package("1.2.3", "4.0.0").major_versions_behind(), Some(3));
+        assert_eq!(package("v2.1", "2.5.0").major_versions_behind(), Some(0));
+        assert_eq!(package("3.0.0", "2.0.0").major_versions_behind(), Some(0));
+        assert_eq!(package("dev-main", "4.0.0").major_versions_behind(), None);
+        assert_eq!(package("1.2.3", "4.0.0").staleness(), Staleness::Behind(3));
+        assert_eq!(package("1.2.3", "1.3.0").staleness(), Staleness::Current);
+        assert_eq!(package("1.2.3", "2.x-dev").staleness(), Staleness::Current);
+        assert_eq!(Staleness::Behind(3).to_string(), "3 major versions behind");
+        assert_eq!(Staleness::Behind(1).to_string(), "1 major version behind");
+    }
+
+    #[test]
+    fn test_version_delta() {
+        assert_eq!(
+            package("1.2.3", "2.0.0").version_delta(),
+            Some(VersionBump::Major)
+        );
+        assert_eq!(
+            package("v1.2.3", "v1.3.0").version_delta(),
+            Some(VersionBump::Minor)
+        );
+        assert_eq!(
+            package("1.2.3", "1.2.4").version_delta(),
+            Some(VersionBump::Patch)
+        );
+        assert_eq!(package("1.2.3", "1.2.3").version_delta(), None);
+        assert_eq!(
+            package("dev-main", "1.2.3").version_delta(),
+            Some(VersionBump::Branch)
+        );
+        assert_eq!(
+            package("9999999-dev", "9999999-dev").version_delta(),
+            Some(VersionBump::Branch)
+        );
+        assert_eq!(
+            package("1.2.3", "2.x-dev").version_delta(),
+            Some(VersionBump::Branch)
+        );
+        assert_eq!(package("1.2.3", "not-a-version").version_delta(), None);
+    }
+
+    #[test]
+    fn test_update_class() {
+        assert_eq!(package("1.2.3", "1.2.4").update_class(), UpdateClass::Patch);
+        assert_eq!(package("1.2.3", "1.3.0").update_class(), UpdateClass::Minor);
+        assert_eq!(package("1.2.3", "3.0.0").update_class(), UpdateClass::Major);
+        assert_eq!(package("0.2.0", "0.3.0").update_class(), UpdateClass::Minor);
+        assert_eq!(
+            package("1.0.0-beta1", "1.0.0").update_class(),
+            UpdateClass::Patch
+        );
+        assert_eq!(
+            package("1.2.3", "1.3.0-RC1").update_class(),
+            UpdateClass::Minor
+        );
+        assert_eq!(
+            package("2.0.0-alpha", "2.0.0-beta").update_class(),
+            UpdateClass::Patch
+        );
+        assert_eq!(package("1.2.3", "1.2.3").update_class(), UpdateClass::None);
+        assert_eq!(package("1.3.0", "1.2.9").update_class(), UpdateClass::None);
+        assert_eq!(
+            package("dev-main", "1.2.3").update_class(),
+            UpdateClass::None
+        );
+        assert_eq!(
+            package("1.2.3", "not-a-version").update_class(),
+            UpdateClass::None
+        );
+        assert_eq!(UpdateClass::Minor.to_string(), "minor");
+    }
+}